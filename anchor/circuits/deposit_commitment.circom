@@ -0,0 +1,24 @@
+pragma circom 2.0.0;
+include "./circomlib/bitify.circom";
+include "./commitment-hasher.circom";
+
+// Proves a deposited leaf is Poseidon(nullifier, secret) for a nullifier and secret
+// of at most 31 bytes, the note format `withdraw` can spend. Checked by `deposit`
+// before the leaf is inserted; an amount-hiding deposit would extend this statement.
+template DepositCommitment() {
+    signal input commitment;
+    signal input nullifier;
+    signal input secret;
+
+    component nullifierBits = Num2Bits(248);
+    nullifierBits.in <== nullifier;
+    component secretBits = Num2Bits(248);
+    secretBits.in <== secret;
+
+    component commitmentHasher = CommitmentHasher();
+    commitmentHasher.nullifier <== nullifier;
+    commitmentHasher.secret <== secret;
+    commitmentHasher.commitment === commitment;
+}
+
+component main {public [commitment]} = DepositCommitment();
@@ -1,3 +1,4 @@
 pragma circom 2.0.0;
+include "./withdraw.circom";
 // 32 levels of the merkle tree (root not counted)
 component main = Withdraw(31);
\ No newline at end of file
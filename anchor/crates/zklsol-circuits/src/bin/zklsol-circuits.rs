@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+use std::process;
+use zklsol_circuits::ceremony::{chain, contribute, finalize, snarkjs_verify, verify_chain};
+use zklsol_circuits::check::check_rebuild;
+use zklsol_circuits::pipeline::{build, compile, setup};
+use zklsol_circuits::{check_entry, BuildError, CircuitEntry, CircuitPaths, Lock, Manifest, Tools};
+
+const USAGE: &str = "usage (from the anchor directory, or pass --root <dir>):
+  zklsol-circuits build [circuit...]
+  zklsol-circuits check [--rebuild] [circuit...]
+  zklsol-circuits ceremony init <circuit>
+  zklsol-circuits ceremony contribute <circuit> <name>
+  zklsol-circuits ceremony finalize <circuit>
+  zklsol-circuits ceremony verify [--skip-snarkjs] <circuit>
+
+circom and snarkjs are taken from $CIRCOM and $SNARKJS.";
+
+fn selected<'a>(
+    manifest: &'a Manifest,
+    names: &[String],
+) -> Result<Vec<&'a CircuitEntry>, BuildError> {
+    if names.is_empty() {
+        return Ok(manifest.circuits.iter().collect());
+    }
+    names.iter().map(|name| manifest.entry(name)).collect()
+}
+
+/// Exit status: 0 when everything matches, 1 on drift.
+fn run(root: PathBuf, args: &[String]) -> Result<i32, BuildError> {
+    let manifest = Manifest::load(&root)?;
+    let rebuild = args.iter().any(|a| a == "--rebuild");
+    let skip_snarkjs = args.iter().any(|a| a == "--skip-snarkjs");
+    let args: Vec<String> = args
+        .iter()
+        .filter(|a| *a != "--rebuild" && *a != "--skip-snarkjs")
+        .cloned()
+        .collect();
+    match args.split_first() {
+        Some((command, names)) if command == "build" => {
+            let tools = Tools::from_env();
+            let mut lock = Lock::load(&root)?;
+            for entry in selected(&manifest, names)? {
+                println!("building {} (depth {})", entry.circuit, entry.depth);
+                lock.insert(build(&root, &manifest, entry, &tools)?);
+            }
+            lock.save(&root)?;
+            Ok(0)
+        }
+        Some((command, names)) if command == "check" => {
+            let tools = Tools::from_env();
+            let lock = Lock::load(&root)?;
+            let scratch = std::env::temp_dir().join(format!("zklsol-circuits-{}", process::id()));
+            let mut drifts = Vec::new();
+            for entry in selected(&manifest, names)? {
+                drifts.extend(check_entry(&root, entry, &lock)?);
+                if rebuild {
+                    drifts.extend(check_rebuild(
+                        &root, &scratch, &manifest, entry, &lock, &tools,
+                    )?);
+                }
+            }
+            if rebuild {
+                let _ = std::fs::remove_dir_all(&scratch);
+            }
+            for drift in &drifts {
+                eprintln!("{}: {}", drift.circuit, drift.message);
+            }
+            if drifts.is_empty() {
+                println!("all circuits match the manifest");
+                return Ok(0);
+            }
+            Ok(1)
+        }
+        Some((command, rest)) if command == "ceremony" && rest.len() >= 2 => {
+            let tools = Tools::from_env();
+            let entry = manifest.entry(&rest[1])?;
+            let paths = CircuitPaths::new(&root, &root, &entry.circuit);
+            match (rest[0].as_str(), &rest[2..]) {
+                ("init", []) => {
+                    compile(&paths, &tools)?;
+                    setup(&root, &paths, entry, &tools)?;
+                    println!("{}", paths.initial_zkey.display());
+                }
+                ("contribute", [name]) => {
+                    println!("{}", contribute(&paths, name, &tools)?.display());
+                }
+                ("finalize", []) => {
+                    let mut lock = Lock::load(&root)?;
+                    lock.insert(finalize(&paths, &manifest, entry, &tools)?);
+                    lock.save(&root)?;
+                }
+                ("verify", []) => {
+                    let transcript = verify_chain(&paths)?;
+                    print!("{}", transcript.render());
+                    if !skip_snarkjs {
+                        if let Some(last) = chain(&paths).last() {
+                            snarkjs_verify(&root, &paths, entry, last, &tools)?;
+                        }
+                    }
+                    if !transcript.drifts.is_empty() {
+                        return Ok(1);
+                    }
+                }
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return Ok(2);
+                }
+            }
+            Ok(0)
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            Ok(2)
+        }
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut root = PathBuf::from(".");
+    if let Some(position) = args.iter().position(|a| a == "--root") {
+        if position + 1 >= args.len() {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+        root = PathBuf::from(args.remove(position + 1));
+        args.remove(position);
+    }
+    match run(root, &args) {
+        Ok(status) => process::exit(status),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
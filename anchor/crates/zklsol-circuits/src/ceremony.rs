@@ -0,0 +1,268 @@
+//! Phase-2 ceremony over `<circuit>_0000.zkey` → `<circuit>_0001.zkey` → … → `<circuit>_final.zkey`.
+//! Contributions and the full check are done by snarkjs; the chain itself is checked here so
+//! the transcript can be read without trusting the files' order or names.
+use crate::check::Drift;
+use crate::error::BuildError;
+use crate::manifest::{to_hex, CircuitEntry, LockEntry, Manifest};
+use crate::pipeline::{
+    apply_beacon, export_verification_key, lock_entry, path_str, require_file, write_key_modules,
+    CircuitPaths, Tools,
+};
+use crate::zkey::{ContributionKind, Zkey};
+use rand::Rng;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zklsol_snarkjs::VerifyingKeyBytes;
+
+fn numbered(paths: &CircuitPaths) -> usize {
+    (0..)
+        .take_while(|index| paths.contribution_zkey(*index).is_file())
+        .count()
+}
+
+/// The zkeys of a ceremony in order: the numbered ones, then the final one if it exists.
+pub fn chain(paths: &CircuitPaths) -> Vec<PathBuf> {
+    let mut chain: Vec<PathBuf> = (0..numbered(paths))
+        .map(|index| paths.contribution_zkey(index))
+        .collect();
+    if paths.zkey.is_file() {
+        chain.push(paths.zkey.clone());
+    }
+    chain
+}
+
+/// Adds a contribution on top of the last numbered zkey, with entropy from the OS.
+pub fn contribute(paths: &CircuitPaths, name: &str, tools: &Tools) -> Result<PathBuf, BuildError> {
+    let numbered = numbered(paths);
+    if numbered == 0 {
+        return Err(BuildError::MissingInput(
+            paths.initial_zkey.display().to_string(),
+        ));
+    }
+    let from = paths.contribution_zkey(numbered - 1);
+    let to = paths.contribution_zkey(numbered);
+    let entropy = to_hex(&rand::rng().random::<[u8; 32]>());
+    tools.snarkjs(&[
+        "zkey",
+        "contribute",
+        path_str(&from),
+        path_str(&to),
+        &format!("--name={}", name),
+        &format!("-e={}", entropy),
+    ])?;
+    Ok(to)
+}
+
+/// Closes the ceremony with the manifest beacon and regenerates the keys from the result.
+pub fn finalize(
+    paths: &CircuitPaths,
+    manifest: &Manifest,
+    entry: &CircuitEntry,
+    tools: &Tools,
+) -> Result<LockEntry, BuildError> {
+    let numbered = numbered(paths);
+    if numbered == 0 {
+        return Err(BuildError::MissingInput(
+            paths.initial_zkey.display().to_string(),
+        ));
+    }
+    apply_beacon(
+        paths,
+        &paths.contribution_zkey(numbered - 1),
+        manifest,
+        entry,
+        tools,
+    )?;
+    export_verification_key(paths, tools)?;
+    write_key_modules(paths)?;
+    lock_entry(paths, entry)
+}
+
+/// Runs `snarkjs zkey verify`, which recomputes the zkey from the r1cs and ptau and checks
+/// every contribution's proof of knowledge.
+pub fn snarkjs_verify(
+    root: &Path,
+    paths: &CircuitPaths,
+    entry: &CircuitEntry,
+    zkey: &Path,
+    tools: &Tools,
+) -> Result<(), BuildError> {
+    let ptau = root.join(&entry.ptau);
+    require_file(&paths.r1cs)?;
+    require_file(&ptau)?;
+    tools.snarkjs(&[
+        "zkey",
+        "verify",
+        path_str(&paths.r1cs),
+        path_str(&ptau),
+        path_str(zkey),
+    ])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContributionRecord {
+    /// 1-based, like snarkjs prints them.
+    pub index: usize,
+    pub kind: ContributionKind,
+    pub name: Option<String>,
+    pub beacon_hash: Option<Vec<u8>>,
+    pub hash: [u8; 64],
+    /// The zkey this contribution produced.
+    pub zkey: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub circuit: String,
+    pub cs_hash: [u8; 64],
+    pub contributions: Vec<ContributionRecord>,
+    pub drifts: Vec<Drift>,
+}
+
+/// 64 byte hash as snarkjs formats it, four rows of four 8-digit groups.
+pub fn format_hash(hash: &[u8; 64]) -> String {
+    hash.chunks(16)
+        .map(|row| {
+            let groups: Vec<String> = row.chunks(4).map(to_hex).collect();
+            format!("\t\t{}", groups.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Transcript {
+    fn fail(&mut self, message: String) {
+        self.drifts.push(Drift {
+            circuit: self.circuit.clone(),
+            message,
+        });
+    }
+
+    pub fn render(&self) -> String {
+        let mut s = format!(
+            "{}: {} contribution(s)\ncircuit hash:\n{}\n",
+            self.circuit,
+            self.contributions.len(),
+            format_hash(&self.cs_hash)
+        );
+        for record in &self.contributions {
+            let label = match record.kind {
+                ContributionKind::Contribution => "contribution",
+                ContributionKind::Beacon => "beacon",
+            };
+            s.push_str(&format!(
+                "#{} {} {} ({})\n{}\n",
+                record.index,
+                label,
+                record.name.as_deref().unwrap_or("<unnamed>"),
+                record.zkey.display(),
+                format_hash(&record.hash)
+            ));
+            if let Some(beacon) = &record.beacon_hash {
+                s.push_str(&format!("\tbeacon hash {}\n", to_hex(beacon)));
+            }
+        }
+        for drift in &self.drifts {
+            s.push_str(&format!("FAILED: {}\n", drift.message));
+        }
+        s
+    }
+}
+
+/// Reads the chain and checks that each zkey adds exactly one contribution to the previous
+/// one, that every stored transcript and delta is consistent, and that the final zkey is the
+/// one `verification_key.json` was exported from.
+pub fn verify_chain(paths: &CircuitPaths) -> Result<Transcript, BuildError> {
+    let files = chain(paths);
+    let mut transcript = Transcript {
+        circuit: paths.circuit.clone(),
+        cs_hash: [0u8; 64],
+        contributions: Vec::new(),
+        drifts: Vec::new(),
+    };
+    let Some(first) = files.first() else {
+        return Err(BuildError::MissingInput(
+            paths.initial_zkey.display().to_string(),
+        ));
+    };
+    let initial = Zkey::read(first)?;
+    transcript.cs_hash = initial.cs_hash;
+    if *first == paths.initial_zkey && !initial.contributions.is_empty() {
+        transcript.fail(format!("{} already has contributions", first.display()));
+    }
+
+    let mut previous = initial;
+    for (index, path) in files.iter().enumerate() {
+        let zkey = if index == 0 {
+            previous.clone()
+        } else {
+            Zkey::read(path)?
+        };
+        if index > 0 {
+            if zkey.cs_hash != previous.cs_hash
+                || zkey.alpha_g1 != previous.alpha_g1
+                || zkey.beta_g2 != previous.beta_g2
+                || zkey.gamma_g2 != previous.gamma_g2
+            {
+                transcript.fail(format!(
+                    "{} is for a different circuit setup",
+                    path.display()
+                ));
+            }
+            if zkey.contributions.len() != previous.contributions.len() + 1
+                || zkey.contributions[..previous.contributions.len()] != previous.contributions[..]
+            {
+                transcript.fail(format!(
+                    "{} doesn't extend {} by one contribution",
+                    path.display(),
+                    files[index - 1].display()
+                ));
+            }
+        }
+        for (i, contribution) in zkey.contributions.iter().enumerate() {
+            if contribution.transcript != zkey.expected_transcript(i) {
+                transcript.fail(format!(
+                    "{} contribution #{} transcript",
+                    path.display(),
+                    i + 1
+                ));
+            }
+        }
+        if let Some(last) = zkey.contributions.last() {
+            if last.delta_after != zkey.delta_g1 {
+                transcript.fail(format!(
+                    "{} delta doesn't match its last contribution",
+                    path.display()
+                ));
+            }
+            if index > 0 {
+                transcript.contributions.push(ContributionRecord {
+                    index: zkey.contributions.len(),
+                    kind: last.kind,
+                    name: last.name.clone(),
+                    beacon_hash: last.beacon_hash.clone(),
+                    hash: last.hash(),
+                    zkey: path.clone(),
+                });
+            }
+        }
+        previous = zkey;
+    }
+
+    if *files.last().unwrap() == paths.zkey && paths.verification_key.is_file() {
+        let key = VerifyingKeyBytes::from_json_str(&fs::read_to_string(&paths.verification_key)?)?;
+        if key.vk_alpha_g1 != previous.alpha_g1
+            || key.vk_beta_g2 != previous.beta_g2
+            || key.vk_gamme_g2 != previous.gamma_g2
+            || key.vk_delta_g2 != previous.delta_g2
+            || key.nr_pubinputs != previous.n_public as u64 + 1
+        {
+            transcript.fail(format!(
+                "{} wasn't exported from {}",
+                paths.verification_key.display(),
+                paths.zkey.display()
+            ));
+        }
+    }
+    Ok(transcript)
+}
@@ -0,0 +1,165 @@
+//! Drift between the manifest, the committed artifacts and the keys compiled into the program.
+use crate::error::BuildError;
+use crate::manifest::{circuit_levels, sha256_file, CircuitEntry, Lock, LockEntry, Manifest};
+use crate::pipeline::{build_artifacts, CircuitPaths, Tools};
+use std::fs;
+use std::path::Path;
+use zklsol::withdraw_verifier::{WithdrawVerifyingKey, VERIFYING_KEYS};
+use zklsol_snarkjs::VerifyingKeyBytes;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub circuit: String,
+    pub message: String,
+}
+
+struct Report<'a> {
+    circuit: &'a str,
+    drifts: Vec<Drift>,
+}
+
+impl Report<'_> {
+    fn push(&mut self, message: String) {
+        self.drifts.push(Drift {
+            circuit: self.circuit.to_string(),
+            message,
+        });
+    }
+}
+
+fn registered_key_matches(key: &WithdrawVerifyingKey, expected: &VerifyingKeyBytes) -> bool {
+    let WithdrawVerifyingKey::Groth16(key) = key else {
+        return false;
+    };
+    key.nr_pubinputs as u64 == expected.nr_pubinputs
+        && key.vk_alpha_g1 == expected.vk_alpha_g1
+        && key.vk_beta_g2 == expected.vk_beta_g2
+        && key.vk_gamme_g2 == expected.vk_gamme_g2
+        && key.vk_delta_g2 == expected.vk_delta_g2
+        && key.vk_ic == expected.vk_ic.as_slice()
+}
+
+fn check_digest(report: &mut Report, path: &Path, expected: &str) -> Result<(), BuildError> {
+    if path.is_file() && sha256_file(path)? != expected {
+        report.push(format!("{} doesn't match the lock", path.display()));
+    }
+    Ok(())
+}
+
+/// Checks one manifest entry against what is committed, without running circom or snarkjs.
+pub fn check_entry(
+    root: &Path,
+    entry: &CircuitEntry,
+    lock: &Lock,
+) -> Result<Vec<Drift>, BuildError> {
+    let paths = CircuitPaths::new(root, root, &entry.circuit);
+    let mut report = Report {
+        circuit: &entry.circuit,
+        drifts: Vec::new(),
+    };
+
+    // Depth 0 marks circuits without a Merkle tree, like `deposit_commitment`.
+    if entry.depth > 0 {
+        let levels = circuit_levels(&fs::read_to_string(&paths.source)?)
+            .ok_or_else(|| BuildError::MissingMainComponent(paths.source.display().to_string()))?;
+        if levels + 1 != entry.depth {
+            report.push(format!(
+                "{} builds {} levels below the root, depth {} needs {}",
+                paths.source.display(),
+                levels,
+                entry.depth,
+                entry.depth - 1
+            ));
+        }
+    }
+
+    if !paths.verification_key.is_file() {
+        report.push(format!(
+            "{} is missing, run `zklsol-circuits build {}`",
+            paths.verification_key.display(),
+            entry.circuit
+        ));
+        return Ok(report.drifts);
+    }
+    let key = VerifyingKeyBytes::from_json_str(&fs::read_to_string(&paths.verification_key)?)?;
+    let source = key.to_rust_source();
+    for module in [&paths.rust_module, &paths.program_module] {
+        match fs::read_to_string(module) {
+            Ok(committed) if committed == source => {}
+            Ok(_) => report.push(format!(
+                "{} doesn't match the verifying key",
+                module.display()
+            )),
+            Err(_) => report.push(format!("{} is missing", module.display())),
+        }
+    }
+
+    match VERIFYING_KEYS
+        .iter()
+        .find(|registered| registered.circuit == entry.circuit)
+    {
+        None => report.push("not registered in VERIFYING_KEYS".to_string()),
+        Some(registered) => {
+            if registered.depth != entry.depth {
+                report.push(format!(
+                    "registered for depth {}, manifest says {}",
+                    registered.depth, entry.depth
+                ));
+            }
+            if !registered_key_matches(&registered.key, &key) {
+                report.push("key compiled into the program doesn't match".to_string());
+            }
+        }
+    }
+
+    match lock.entry(&entry.circuit) {
+        None => report.push("no lock entry".to_string()),
+        Some(locked) => {
+            if locked.depth != entry.depth {
+                report.push(format!("locked at depth {}", locked.depth));
+            }
+            check_digest(&mut report, &paths.r1cs, &locked.r1cs)?;
+            check_digest(
+                &mut report,
+                &paths.verification_key,
+                &locked.verification_key,
+            )?;
+            if let Some(zkey) = &locked.zkey {
+                check_digest(&mut report, &paths.zkey, zkey)?;
+            }
+        }
+    }
+    Ok(report.drifts)
+}
+
+/// Rebuilds into `scratch` and compares with the lock, catching sources edited without a rebuild.
+pub fn check_rebuild(
+    root: &Path,
+    scratch: &Path,
+    manifest: &Manifest,
+    entry: &CircuitEntry,
+    lock: &Lock,
+    tools: &Tools,
+) -> Result<Vec<Drift>, BuildError> {
+    let rebuilt = build_artifacts(root, scratch, manifest, entry, tools)?;
+    let mut report = Report {
+        circuit: &entry.circuit,
+        drifts: Vec::new(),
+    };
+    let Some(locked) = lock.entry(&entry.circuit) else {
+        report.push("no lock entry".to_string());
+        return Ok(report.drifts);
+    };
+    let LockEntry {
+        r1cs,
+        verification_key,
+        ..
+    } = rebuilt;
+    if r1cs != locked.r1cs {
+        report.push("r1cs rebuilt from the sources differs".to_string());
+    }
+    if verification_key != locked.verification_key {
+        report.push("verifying key rebuilt from the sources differs".to_string());
+    }
+    Ok(report.drifts)
+}
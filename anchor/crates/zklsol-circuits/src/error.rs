@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Convert(#[from] zklsol_snarkjs::ConvertError),
+    #[error("circuit {0} is not in the manifest")]
+    UnknownCircuit(String),
+    #[error("missing input {0}")]
+    MissingInput(String),
+    #[error("{command} exited with {status}")]
+    Tool { command: String, status: String },
+    #[error("{0} doesn't declare `component main = Withdraw(levels)`")]
+    MissingMainComponent(String),
+    #[error("invalid zkey: {0}")]
+    InvalidZkey(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,12 @@
+pub mod ceremony;
+pub mod check;
+pub mod error;
+pub mod manifest;
+pub mod pipeline;
+pub mod zkey;
+
+pub use check::{check_entry, Drift};
+pub use error::BuildError;
+pub use manifest::{CircuitEntry, Lock, LockEntry, Manifest};
+pub use pipeline::{CircuitPaths, Tools};
+pub use zkey::Zkey;
@@ -0,0 +1,119 @@
+//! `circuits/manifest.json` lists what to build, `circuits/manifest.lock.json`
+//! records the digests of what was built from it.
+use crate::error::BuildError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+pub const MANIFEST: &str = "circuits/manifest.json";
+pub const LOCK: &str = "circuits/manifest.lock.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitEntry {
+    /// `circuits/<circuit>.circom`, also the name of its output directory and key module.
+    pub circuit: String,
+    pub depth: u64,
+    /// Phase-1 powers of tau, relative to the anchor directory.
+    pub ptau: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Phase-2 beacon applied to every zkey, keeps the setup reproducible.
+    pub beacon: String,
+    pub beacon_iterations: u32,
+    pub circuits: Vec<CircuitEntry>,
+}
+
+impl Manifest {
+    pub fn load(root: &Path) -> Result<Self, BuildError> {
+        Ok(serde_json::from_str(&fs::read_to_string(
+            root.join(MANIFEST),
+        )?)?)
+    }
+
+    pub fn entry(&self, circuit: &str) -> Result<&CircuitEntry, BuildError> {
+        self.circuits
+            .iter()
+            .find(|entry| entry.circuit == circuit)
+            .ok_or_else(|| BuildError::UnknownCircuit(circuit.to_string()))
+    }
+}
+
+/// sha256 of the artifacts a build produced, hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    pub circuit: String,
+    pub depth: u64,
+    pub r1cs: String,
+    /// Not every final zkey is committed, the verifying key is what the program uses.
+    pub zkey: Option<String>,
+    pub verification_key: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lock {
+    pub circuits: Vec<LockEntry>,
+}
+
+impl Lock {
+    /// An empty lock when nothing was built yet.
+    pub fn load(root: &Path) -> Result<Self, BuildError> {
+        let path = root.join(LOCK);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, root: &Path) -> Result<(), BuildError> {
+        fs::write(root.join(LOCK), serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn entry(&self, circuit: &str) -> Option<&LockEntry> {
+        self.circuits.iter().find(|entry| entry.circuit == circuit)
+    }
+
+    /// Replaces the entry for the same circuit, keeping manifest order otherwise.
+    pub fn insert(&mut self, entry: LockEntry) {
+        match self
+            .circuits
+            .iter_mut()
+            .find(|existing| existing.circuit == entry.circuit)
+        {
+            Some(existing) => *existing = entry,
+            None => self.circuits.push(entry),
+        }
+    }
+}
+
+/// `N` in `component main = Withdraw(N);`, the levels below the root.
+pub fn circuit_levels(source: &str) -> Option<u64> {
+    source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .find_map(|line| {
+            let rest = line.split("component main").nth(1)?;
+            let args = rest.split("Withdraw(").nth(1)?;
+            args.split(')').next()?.trim().parse().ok()
+        })
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+/// Lowercase hex, two digits per byte.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, BuildError> {
+    Ok(sha256_hex(&fs::read(path)?))
+}
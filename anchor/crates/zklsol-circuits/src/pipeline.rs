@@ -0,0 +1,220 @@
+//! The steps of `scripts/compile-circuit.sh` and `scripts/generate-vkey.sh`, with the
+//! random phase-2 contribution replaced by the manifest beacon so reruns match.
+use crate::error::BuildError;
+use crate::manifest::{sha256_file, CircuitEntry, LockEntry, Manifest};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zklsol_snarkjs::VerifyingKeyBytes;
+
+/// Where a circuit's source, artifacts and key modules live below the anchor directory.
+#[derive(Debug, Clone)]
+pub struct CircuitPaths {
+    pub circuit: String,
+    pub source: PathBuf,
+    pub output: PathBuf,
+    pub r1cs: PathBuf,
+    pub initial_zkey: PathBuf,
+    pub zkey: PathBuf,
+    pub verification_key: PathBuf,
+    pub rust_module: PathBuf,
+    pub program_module: PathBuf,
+}
+
+impl CircuitPaths {
+    /// Artifacts go under `output_root`, normally `root` itself.
+    pub fn new(root: &Path, output_root: &Path, circuit: &str) -> Self {
+        let output = output_root.join("circuits-output").join(circuit);
+        let module = format!("{}_verifying_key.rs", circuit);
+        Self {
+            circuit: circuit.to_string(),
+            source: root.join("circuits").join(format!("{}.circom", circuit)),
+            r1cs: output.join(format!("{}.r1cs", circuit)),
+            initial_zkey: output.join(format!("{}_{:04}.zkey", circuit, 0)),
+            zkey: output.join(format!("{}_final.zkey", circuit)),
+            verification_key: output.join("verification_key.json"),
+            rust_module: output_root
+                .join("circuits-output")
+                .join(format!("{}_rs", circuit))
+                .join(&module),
+            program_module: output_root.join("programs/zklsol/src").join(module),
+            output,
+        }
+    }
+
+    /// `<circuit>_NNNN.zkey`, index 0 being the output of `setup`.
+    pub fn contribution_zkey(&self, index: usize) -> PathBuf {
+        self.output
+            .join(format!("{}_{:04}.zkey", self.circuit, index))
+    }
+}
+
+/// External commands, overridable with `CIRCOM` and `SNARKJS` (e.g. `SNARKJS="npx snarkjs"`).
+#[derive(Debug, Clone)]
+pub struct Tools {
+    pub circom: String,
+    pub snarkjs: String,
+}
+
+impl Tools {
+    pub fn from_env() -> Self {
+        Self {
+            circom: std::env::var("CIRCOM").unwrap_or_else(|_| "circom".to_string()),
+            snarkjs: std::env::var("SNARKJS").unwrap_or_else(|_| "snarkjs".to_string()),
+        }
+    }
+
+    fn run(&self, tool: &str, args: &[&str]) -> Result<(), BuildError> {
+        let mut words = tool.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| BuildError::MissingInput("tool command".to_string()))?;
+        let status = Command::new(program).args(words).args(args).status()?;
+        if !status.success() {
+            return Err(BuildError::Tool {
+                command: format!("{} {}", tool, args.join(" ")),
+                status: status.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn circom(&self, args: &[&str]) -> Result<(), BuildError> {
+        self.run(&self.circom, args)
+    }
+
+    pub fn snarkjs(&self, args: &[&str]) -> Result<(), BuildError> {
+        self.run(&self.snarkjs, args)
+    }
+}
+
+pub(crate) fn path_str(path: &Path) -> &str {
+    path.to_str().expect("artifact paths are utf-8")
+}
+
+pub(crate) fn require_file(path: &Path) -> Result<(), BuildError> {
+    if !path.is_file() {
+        return Err(BuildError::MissingInput(path.display().to_string()));
+    }
+    Ok(())
+}
+
+/// `circom` with the flags of `scripts/compile-circuit.sh`.
+pub fn compile(paths: &CircuitPaths, tools: &Tools) -> Result<(), BuildError> {
+    require_file(&paths.source)?;
+    fs::create_dir_all(&paths.output)?;
+    let output_dir = format!("{}/", path_str(&paths.output));
+    tools.circom(&[
+        path_str(&paths.source),
+        "--c",
+        "--json",
+        "--r1cs",
+        "--wasm",
+        "--sym",
+        "--output",
+        &output_dir,
+    ])
+}
+
+/// `<circuit>_0000.zkey` from the r1cs and the phase-1 ptau, before any contribution.
+pub fn setup(
+    root: &Path,
+    paths: &CircuitPaths,
+    entry: &CircuitEntry,
+    tools: &Tools,
+) -> Result<(), BuildError> {
+    let ptau = root.join(&entry.ptau);
+    require_file(&paths.r1cs)?;
+    require_file(&ptau)?;
+    tools.snarkjs(&[
+        "zkey",
+        "new",
+        path_str(&paths.r1cs),
+        path_str(&ptau),
+        path_str(&paths.initial_zkey),
+    ])
+}
+
+/// Applies the manifest beacon to `from`, producing `<circuit>_final.zkey`.
+pub fn apply_beacon(
+    paths: &CircuitPaths,
+    from: &Path,
+    manifest: &Manifest,
+    entry: &CircuitEntry,
+    tools: &Tools,
+) -> Result<(), BuildError> {
+    require_file(from)?;
+    let iterations = manifest.beacon_iterations.to_string();
+    let name = format!("-n={} beacon", entry.circuit);
+    tools.snarkjs(&[
+        "zkey",
+        "beacon",
+        path_str(from),
+        path_str(&paths.zkey),
+        &manifest.beacon,
+        &iterations,
+        &name,
+    ])
+}
+
+pub fn export_verification_key(paths: &CircuitPaths, tools: &Tools) -> Result<(), BuildError> {
+    tools.snarkjs(&[
+        "zkey",
+        "export",
+        "verificationkey",
+        path_str(&paths.zkey),
+        path_str(&paths.verification_key),
+    ])
+}
+
+pub fn lock_entry(paths: &CircuitPaths, entry: &CircuitEntry) -> Result<LockEntry, BuildError> {
+    Ok(LockEntry {
+        circuit: entry.circuit.clone(),
+        depth: entry.depth,
+        r1cs: sha256_file(&paths.r1cs)?,
+        zkey: Some(sha256_file(&paths.zkey)?),
+        verification_key: sha256_file(&paths.verification_key)?,
+    })
+}
+
+/// Compiles the circuit, runs the phase-2 setup with the beacon and exports the verifying key
+/// into `output_root`. Returns the digests without touching the lock or the program.
+pub fn build_artifacts(
+    root: &Path,
+    output_root: &Path,
+    manifest: &Manifest,
+    entry: &CircuitEntry,
+    tools: &Tools,
+) -> Result<LockEntry, BuildError> {
+    let paths = CircuitPaths::new(root, output_root, &entry.circuit);
+    compile(&paths, tools)?;
+    setup(root, &paths, entry, tools)?;
+    apply_beacon(&paths, &paths.initial_zkey, manifest, entry, tools)?;
+    export_verification_key(&paths, tools)?;
+    lock_entry(&paths, entry)
+}
+
+/// Writes `circuits-output/<circuit>_rs/<circuit>_verifying_key.rs` and its copy in the program.
+pub fn write_key_modules(paths: &CircuitPaths) -> Result<(), BuildError> {
+    let source = VerifyingKeyBytes::from_json_str(&fs::read_to_string(&paths.verification_key)?)?
+        .to_rust_source();
+    for module in [&paths.rust_module, &paths.program_module] {
+        if let Some(dir) = module.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(module, &source)?;
+    }
+    Ok(())
+}
+
+/// Full build in place: artifacts, key modules, lock entry.
+pub fn build(
+    root: &Path,
+    manifest: &Manifest,
+    entry: &CircuitEntry,
+    tools: &Tools,
+) -> Result<LockEntry, BuildError> {
+    let lock_entry = build_artifacts(root, root, manifest, entry, tools)?;
+    write_key_modules(&CircuitPaths::new(root, root, &entry.circuit))?;
+    Ok(lock_entry)
+}
@@ -0,0 +1,277 @@
+//! Reader for the parts of a snarkjs Groth16 `.zkey` a ceremony touches: the header keys
+//! (section 2) and the MPC parameters (section 10). Curve points are stored little-endian
+//! in Montgomery form and returned in the big-endian layout used everywhere else.
+use crate::error::BuildError;
+use ark_bn254::Fq;
+use ark_ff::{BigInteger, Field, PrimeField};
+use blake2::{Blake2b512, Digest};
+use std::fs;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"zkey";
+const GROTH16: u32 = 1;
+const SECTION_PROTOCOL: u32 = 1;
+const SECTION_GROTH16_HEADER: u32 = 2;
+const SECTION_MPC_PARAMS: u32 = 10;
+const N8: usize = 32;
+
+/// Name, iteration exponent and beacon hash parameter keys of a contribution.
+const PARAM_NAME: u8 = 1;
+const PARAM_ITERATIONS: u8 = 2;
+const PARAM_BEACON_HASH: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContributionKind {
+    Contribution,
+    Beacon,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contribution {
+    pub delta_after: [u8; 64],
+    pub g1_s: [u8; 64],
+    pub g1_sx: [u8; 64],
+    pub g2_spx: [u8; 128],
+    pub transcript: [u8; 64],
+    pub kind: ContributionKind,
+    pub name: Option<String>,
+    pub num_iterations_exp: Option<u8>,
+    pub beacon_hash: Option<Vec<u8>>,
+}
+
+impl Contribution {
+    /// snarkjs `hashPubKey`, the hash `snarkjs zkey verify` prints for the contribution.
+    pub fn hash(&self) -> [u8; 64] {
+        let mut hasher = Blake2b512::new();
+        self.hash_pub_key(&mut hasher);
+        digest(hasher)
+    }
+
+    fn hash_pub_key(&self, hasher: &mut Blake2b512) {
+        hasher.update(self.delta_after);
+        hasher.update(self.g1_s);
+        hasher.update(self.g1_sx);
+        hasher.update(self.g2_spx);
+        hasher.update(self.transcript);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zkey {
+    pub n_public: u32,
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g1: [u8; 64],
+    pub delta_g2: [u8; 128],
+    /// Hash of the circuit-specific setup, fixed by `zkey new`.
+    pub cs_hash: [u8; 64],
+    pub contributions: Vec<Contribution>,
+}
+
+impl Zkey {
+    pub fn read(path: &Path) -> Result<Self, BuildError> {
+        Self::parse(&fs::read(path)?).map_err(|e| match e {
+            BuildError::InvalidZkey(message) => {
+                BuildError::InvalidZkey(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, BuildError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err(BuildError::InvalidZkey("not a zkey file".to_string()));
+        }
+        let _version = reader.u32()?;
+        let n_sections = reader.u32()?;
+        let mut sections = Vec::with_capacity(n_sections as usize);
+        for _ in 0..n_sections {
+            let kind = reader.u32()?;
+            let size = reader.u64()? as usize;
+            sections.push((kind, reader.take(size)?));
+        }
+        let section = |kind: u32| {
+            sections
+                .iter()
+                .find(|(k, _)| *k == kind)
+                .map(|(_, data)| Reader {
+                    bytes: data,
+                    pos: 0,
+                })
+                .ok_or_else(|| BuildError::InvalidZkey(format!("missing section {}", kind)))
+        };
+
+        if section(SECTION_PROTOCOL)?.u32()? != GROTH16 {
+            return Err(BuildError::InvalidZkey("not a groth16 zkey".to_string()));
+        }
+        let mut header = section(SECTION_GROTH16_HEADER)?;
+        for _ in 0..2 {
+            let n8 = header.u32()? as usize;
+            if n8 != N8 {
+                return Err(BuildError::InvalidZkey(format!("field size {}", n8)));
+            }
+            header.take(n8)?;
+        }
+        let _n_vars = header.u32()?;
+        let n_public = header.u32()?;
+        let _domain_size = header.u32()?;
+        let alpha_g1 = header.g1()?;
+        let _beta_g1 = header.g1()?;
+        let beta_g2 = header.g2()?;
+        let gamma_g2 = header.g2()?;
+        let delta_g1 = header.g1()?;
+        let delta_g2 = header.g2()?;
+
+        let mut mpc = section(SECTION_MPC_PARAMS)?;
+        let cs_hash = mpc.array()?;
+        let n_contributions = mpc.u32()?;
+        let contributions = (0..n_contributions)
+            .map(|_| mpc.contribution())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            n_public,
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g1,
+            delta_g2,
+            cs_hash,
+            contributions,
+        })
+    }
+
+    /// What snarkjs stores as the transcript of contribution `index`: the circuit hash,
+    /// every earlier contribution and the new contribution's `s` and `s * x`.
+    pub fn expected_transcript(&self, index: usize) -> [u8; 64] {
+        let mut hasher = Blake2b512::new();
+        hasher.update(self.cs_hash);
+        for contribution in &self.contributions[..index] {
+            contribution.hash_pub_key(&mut hasher);
+        }
+        hasher.update(self.contributions[index].g1_s);
+        hasher.update(self.contributions[index].g1_sx);
+        digest(hasher)
+    }
+}
+
+fn digest(hasher: Blake2b512) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    output.copy_from_slice(&hasher.finalize());
+    output
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BuildError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| BuildError::InvalidZkey("truncated".to_string()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], BuildError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, BuildError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, BuildError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, BuildError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    /// One coordinate, Montgomery little-endian to canonical big-endian.
+    fn fq(&mut self) -> Result<[u8; 32], BuildError> {
+        let r_inv = Fq::from(2u64).pow([256u64]).inverse().unwrap();
+        let value = Fq::from_le_bytes_mod_order(self.take(N8)?) * r_inv;
+        let bytes = value.into_bigint().to_bytes_be();
+        let mut output = [0u8; 32];
+        output[32 - bytes.len()..].copy_from_slice(&bytes);
+        Ok(output)
+    }
+
+    fn g1(&mut self) -> Result<[u8; 64], BuildError> {
+        let mut output = [0u8; 64];
+        output[..32].copy_from_slice(&self.fq()?);
+        output[32..].copy_from_slice(&self.fq()?);
+        Ok(output)
+    }
+
+    /// Stored c0, c1 per coordinate, returned as x.c1 | x.c0 | y.c1 | y.c0.
+    fn g2(&mut self) -> Result<[u8; 128], BuildError> {
+        let mut output = [0u8; 128];
+        for offset in [32, 0, 96, 64] {
+            output[offset..offset + 32].copy_from_slice(&self.fq()?);
+        }
+        Ok(output)
+    }
+
+    fn contribution(&mut self) -> Result<Contribution, BuildError> {
+        let delta_after = self.g1()?;
+        let g1_s = self.g1()?;
+        let g1_sx = self.g1()?;
+        let g2_spx = self.g2()?;
+        let transcript = self.array()?;
+        let kind = match self.u32()? {
+            0 => ContributionKind::Contribution,
+            1 => ContributionKind::Beacon,
+            kind => {
+                return Err(BuildError::InvalidZkey(format!(
+                    "contribution type {}",
+                    kind
+                )))
+            }
+        };
+        let params_len = self.u32()? as usize;
+        let mut params = Reader {
+            bytes: self.take(params_len)?,
+            pos: 0,
+        };
+        let mut contribution = Contribution {
+            delta_after,
+            g1_s,
+            g1_sx,
+            g2_spx,
+            transcript,
+            kind,
+            name: None,
+            num_iterations_exp: None,
+            beacon_hash: None,
+        };
+        while params.pos < params.bytes.len() {
+            match params.u8()? {
+                PARAM_NAME => {
+                    let len = params.u8()? as usize;
+                    contribution.name = Some(String::from_utf8_lossy(params.take(len)?).into());
+                }
+                PARAM_ITERATIONS => contribution.num_iterations_exp = Some(params.u8()?),
+                PARAM_BEACON_HASH => {
+                    let len = params.u8()? as usize;
+                    contribution.beacon_hash = Some(params.take(len)?.to_vec());
+                }
+                param => {
+                    return Err(BuildError::InvalidZkey(format!(
+                        "contribution parameter {}",
+                        param
+                    )))
+                }
+            }
+        }
+        Ok(contribution)
+    }
+}
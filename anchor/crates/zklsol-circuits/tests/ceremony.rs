@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::PathBuf;
+use zklsol_circuits::ceremony::{chain, format_hash, verify_chain};
+use zklsol_circuits::zkey::ContributionKind;
+use zklsol_circuits::{BuildError, CircuitPaths, Zkey};
+
+fn anchor_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+#[test]
+fn committed_withdraw3_chain_verifies_test() {
+    let root = anchor_dir();
+    let paths = CircuitPaths::new(&root, &root, "withdraw3");
+    assert_eq!(
+        chain(&paths),
+        vec![paths.initial_zkey.clone(), paths.zkey.clone()]
+    );
+    let transcript = verify_chain(&paths).unwrap();
+    assert_eq!(transcript.drifts, vec![]);
+    assert_eq!(transcript.contributions.len(), 1);
+    let record = &transcript.contributions[0];
+    assert_eq!(record.index, 1);
+    assert_eq!(record.kind, ContributionKind::Contribution);
+    assert_eq!(record.name.as_deref(), Some("1st Contributor"));
+    assert_eq!(record.zkey, paths.zkey);
+
+    let initial = Zkey::read(&paths.initial_zkey).unwrap();
+    let last = Zkey::read(&paths.zkey).unwrap();
+    assert!(initial.contributions.is_empty());
+    assert_eq!(initial.cs_hash, last.cs_hash);
+    assert_eq!(last.n_public, 2);
+    assert_eq!(last.contributions[0].hash(), record.hash);
+    assert_eq!(
+        last.contributions[0].transcript,
+        last.expected_transcript(0)
+    );
+    assert_ne!(initial.delta_g2, last.delta_g2);
+}
+
+#[test]
+fn invalid_zkey_is_rejected_test() {
+    let root = anchor_dir();
+    let paths = CircuitPaths::new(&root, &root, "withdraw3");
+    let bytes = fs::read(&paths.zkey).unwrap();
+    assert!(matches!(
+        Zkey::parse(&bytes[..bytes.len() / 2]),
+        Err(BuildError::InvalidZkey(_))
+    ));
+    assert!(matches!(
+        Zkey::parse(b"r1cs\x01\x00\x00\x00"),
+        Err(BuildError::InvalidZkey(_))
+    ));
+}
+
+#[test]
+fn format_hash_test() {
+    let formatted = format_hash(&[0xab; 64]);
+    let rows: Vec<&str> = formatted.lines().collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], format!("\t\t{}", ["abababab"; 4].join(" ")));
+}
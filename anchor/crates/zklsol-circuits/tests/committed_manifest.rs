@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::PathBuf;
+use zklsol_circuits::manifest::{circuit_levels, sha256_hex};
+use zklsol_circuits::{check_entry, Lock, LockEntry, Manifest};
+
+fn anchor_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+#[test]
+fn committed_keys_match_manifest_test() {
+    let root = anchor_dir();
+    let manifest = Manifest::load(&root).unwrap();
+    let lock = Lock::load(&root).unwrap();
+    for name in ["withdraw3", "withdraw20"] {
+        let entry = manifest.entry(name).unwrap();
+        assert_eq!(
+            check_entry(&root, entry, &lock).unwrap(),
+            vec![],
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn unbuilt_circuit_is_reported_test() {
+    let root = anchor_dir();
+    let manifest = Manifest::load(&root).unwrap();
+    let lock = Lock::load(&root).unwrap();
+    let entry = manifest.entry("withdraw32").unwrap();
+    let drifts = check_entry(&root, entry, &lock).unwrap();
+    assert_eq!(drifts.len(), 1);
+    assert!(drifts[0]
+        .message
+        .contains("zklsol-circuits build withdraw32"));
+}
+
+#[test]
+fn manifest_depths_match_circuit_sources_test() {
+    let root = anchor_dir();
+    for entry in Manifest::load(&root).unwrap().circuits {
+        let source =
+            fs::read_to_string(root.join(format!("circuits/{}.circom", entry.circuit))).unwrap();
+        let expected = entry.depth.checked_sub(1);
+        assert_eq!(circuit_levels(&source), expected, "{}", entry.circuit);
+    }
+}
+
+#[test]
+fn lock_drift_is_reported_test() {
+    let root = anchor_dir();
+    let manifest = Manifest::load(&root).unwrap();
+    let entry = manifest.entry("withdraw3").unwrap();
+    let mut lock = Lock::load(&root).unwrap();
+    let locked = lock.entry("withdraw3").unwrap().clone();
+    lock.insert(LockEntry {
+        verification_key: sha256_hex(b"stale"),
+        ..locked
+    });
+    assert_eq!(lock.circuits.len(), 2);
+    let drifts = check_entry(&root, entry, &lock).unwrap();
+    assert_eq!(drifts.len(), 1);
+    assert!(drifts[0].message.contains("verification_key.json"));
+
+    let depth_mismatch = zklsol_circuits::CircuitEntry {
+        depth: 4,
+        ..entry.clone()
+    };
+    assert!(
+        !check_entry(&root, &depth_mismatch, &Lock::load(&root).unwrap())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn circuit_levels_test() {
+    assert_eq!(
+        circuit_levels("// component main = Withdraw(9);\ncomponent main = Withdraw(19);"),
+        Some(19)
+    );
+    assert_eq!(circuit_levels("template Withdraw(levels) {}"), None);
+}
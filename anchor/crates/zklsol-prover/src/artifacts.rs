@@ -0,0 +1,40 @@
+use crate::error::ProverError;
+use std::path::{Path, PathBuf};
+
+/// Locations of the circom/snarkjs outputs for one circuit, following the
+/// layout produced by `scripts/compile-circuit.sh` and `scripts/generate-vkey.sh`.
+#[derive(Debug, Clone)]
+pub struct CircuitArtifacts {
+    pub name: String,
+    pub wasm: PathBuf,
+    pub r1cs: PathBuf,
+    pub zkey: PathBuf,
+}
+
+impl CircuitArtifacts {
+    pub fn new(circuits_output: impl AsRef<Path>, name: &str) -> Self {
+        let dir = circuits_output.as_ref().join(name);
+        Self {
+            name: name.to_string(),
+            wasm: dir
+                .join(format!("{}_js", name))
+                .join(format!("{}.wasm", name)),
+            r1cs: dir.join(format!("{}.r1cs", name)),
+            zkey: dir.join(format!("{}_final.zkey", name)),
+        }
+    }
+
+    /// Artifacts of `withdrawN.circom` for a pool of the given depth.
+    pub fn withdraw(circuits_output: impl AsRef<Path>, depth: u8) -> Self {
+        Self::new(circuits_output, &format!("withdraw{}", depth))
+    }
+
+    pub fn check_exists(&self) -> Result<(), ProverError> {
+        for path in [&self.wasm, &self.r1cs, &self.zkey] {
+            if !path.is_file() {
+                return Err(ProverError::MissingArtifact(path.display().to_string()));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProverError {
+    #[error("missing circuit artifact {0}")]
+    MissingArtifact(String),
+    #[error("failed to read zkey: {0}")]
+    ReadZkey(String),
+    #[error("failed to compute witness: {0}")]
+    Witness(String),
+    #[error("failed to create proof: {0}")]
+    Prove(String),
+    #[error("generated proof does not verify")]
+    InvalidProof,
+    #[error("expected {expected} path elements, got {actual}")]
+    InvalidPathLength { expected: usize, actual: usize },
+    #[error("path index must be 0 or 1, got {0}")]
+    InvalidPathIndex(u8),
+    #[error("recipient is not below the BN254 scalar field modulus")]
+    InvalidRecipient,
+    #[error("path doesn't lead from the note's commitment to the root")]
+    RootMismatch,
+    #[error("public signals don't match the withdraw arguments")]
+    PublicSignalsMismatch,
+    #[error("note memo can't be decrypted with this viewing key")]
+    InvalidMemo,
+    #[error(transparent)]
+    Convert(#[from] zklsol_snarkjs::ConvertError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,108 @@
+use crate::error::ProverError;
+use anchor_lang::prelude::Pubkey;
+use num_bigint::{BigInt, Sign};
+use zklsol::state::merkle_node::MerkleNode;
+
+/// Secret part of a deposit. Values are 32 byte big-endian field elements
+/// and are reduced modulo the BN254 scalar field like every other leaf.
+#[derive(Debug, Clone)]
+pub struct WithdrawNote {
+    pub nullifier: [u8; 32],
+    pub secret: [u8; 32],
+}
+
+impl WithdrawNote {
+    /// Poseidon(nullifier, secret), the leaf inserted by `deposit`.
+    pub fn commitment(&self) -> [u8; 32] {
+        let nullifier = MerkleNode::deserialize(&self.nullifier);
+        let secret = MerkleNode::deserialize(&self.secret);
+        MerkleNode::from_children(&nullifier, &secret).hash
+    }
+
+    /// Poseidon(nullifier), the seed of the `NullifierHash` account.
+    pub fn nullifier_hash(&self) -> [u8; 32] {
+        MerkleNode::hash(&self.nullifier).hash
+    }
+}
+
+/// Everything needed to build a witness for `withdrawN.circom`.
+#[derive(Debug, Clone)]
+pub struct WithdrawInputs {
+    pub note: WithdrawNote,
+    pub root: [u8; 32],
+    pub recipient: Pubkey,
+    /// Sibling of the path node at every level, leaf level first.
+    pub path_elements: Vec<[u8; 32]>,
+    /// 0 when the path node is the left child, 1 when it is the right child.
+    pub path_indices: Vec<u8>,
+}
+
+impl WithdrawInputs {
+    /// Recipient as the field element the circuit and the on-chain verifier see.
+    pub fn recipient_signal(&self) -> [u8; 32] {
+        MerkleNode::mod_input(&self.recipient.to_bytes())
+    }
+
+    pub fn validate(&self, levels: usize) -> Result<(), ProverError> {
+        for len in [self.path_elements.len(), self.path_indices.len()] {
+            if len != levels {
+                return Err(ProverError::InvalidPathLength {
+                    expected: levels,
+                    actual: len,
+                });
+            }
+        }
+        if let Some(index) = self.path_indices.iter().find(|i| **i > 1) {
+            return Err(ProverError::InvalidPathIndex(*index));
+        }
+        // `withdraw` passes the recipient unreduced, a proof for its reduction wouldn't verify.
+        if self.recipient_signal() != self.recipient.to_bytes() {
+            return Err(ProverError::InvalidRecipient);
+        }
+        if self.path_root() != MerkleNode::mod_input(&self.root) {
+            return Err(ProverError::RootMismatch);
+        }
+        Ok(())
+    }
+
+    /// Root the circuit computes from the note's commitment and the path.
+    pub fn path_root(&self) -> [u8; 32] {
+        let mut node = MerkleNode::deserialize(&self.note.commitment());
+        for (element, index) in self.path_elements.iter().zip(&self.path_indices) {
+            let sibling = MerkleNode::deserialize(element);
+            node = match index {
+                0 => MerkleNode::from_children(&node, &sibling),
+                _ => MerkleNode::from_children(&sibling, &node),
+            };
+        }
+        node.hash
+    }
+
+    /// Named circuit signals, in the same shape `zk-helper.ts` hands to snarkjs.
+    pub fn signals(&self) -> Vec<(&'static str, Vec<BigInt>)> {
+        let mod_bigint = |bytes: &[u8; 32]| to_bigint(&MerkleNode::mod_input(bytes));
+        vec![
+            ("root", vec![mod_bigint(&self.root)]),
+            ("commitment", vec![to_bigint(&self.note.commitment())]),
+            (
+                "nullifierHash",
+                vec![to_bigint(&self.note.nullifier_hash())],
+            ),
+            ("recipient", vec![to_bigint(&self.recipient_signal())]),
+            ("nullifier", vec![mod_bigint(&self.note.nullifier)]),
+            ("secret", vec![mod_bigint(&self.note.secret)]),
+            (
+                "pathElements",
+                self.path_elements.iter().map(mod_bigint).collect(),
+            ),
+            (
+                "pathIndices",
+                self.path_indices.iter().map(|i| BigInt::from(*i)).collect(),
+            ),
+        ]
+    }
+}
+
+pub fn to_bigint(bytes: &[u8; 32]) -> BigInt {
+    BigInt::from_bytes_be(Sign::Plus, bytes)
+}
@@ -0,0 +1,12 @@
+pub mod artifacts;
+pub mod error;
+pub mod inputs;
+pub mod memo;
+pub mod prover;
+
+pub use artifacts::CircuitArtifacts;
+pub use error::ProverError;
+pub use inputs::{WithdrawInputs, WithdrawNote};
+pub use memo::{RecoveredNote, ViewingKey};
+pub use prover::{WithdrawProof, WithdrawProver};
+pub use zklsol_snarkjs::layout;
@@ -0,0 +1,121 @@
+//! Note memos: a deposit's note encrypted to an X25519 viewing key and carried in
+//! `DepositEvent::memo`, so whoever holds the key can recover the notes from the
+//! pool's transaction logs instead of the note string.
+use crate::error::ProverError;
+use crate::inputs::WithdrawNote;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+use zklsol::events::DepositEvent;
+
+const DOMAIN: &[u8] = b"zklsol-note-memo";
+const TAG_SIZE: usize = 16;
+/// Ephemeral public key, then nullifier and secret encrypted with a 16 byte tag.
+pub const NOTE_MEMO_SIZE: usize = 32 + 64 + TAG_SIZE;
+
+/// A note recovered from a pool's deposits, `index` is its leaf index.
+#[derive(Debug, Clone)]
+pub struct RecoveredNote {
+    pub merkle: Pubkey,
+    pub index: u64,
+    pub note: WithdrawNote,
+}
+
+/// Decrypts note memos. Handing out the key lets an auditor see every deposit made
+/// to it, not spend them: spending still needs a proof from the note itself.
+pub struct ViewingKey(StaticSecret);
+
+impl ViewingKey {
+    pub fn random() -> Self {
+        Self(StaticSecret::random())
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(StaticSecret::from(bytes))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// What depositors encrypt to.
+    pub fn public_key(&self) -> [u8; 32] {
+        PublicKey::from(&self.0).to_bytes()
+    }
+
+    pub fn decrypt(&self, memo: &[u8]) -> Result<WithdrawNote, ProverError> {
+        if memo.len() != NOTE_MEMO_SIZE {
+            return Err(ProverError::InvalidMemo);
+        }
+        let ephemeral: [u8; 32] = memo[..32].try_into().unwrap();
+        let shared = self.0.diffie_hellman(&PublicKey::from(ephemeral));
+        let plaintext = cipher(shared.as_bytes(), &ephemeral, &self.public_key())
+            .decrypt(&Nonce::default(), &memo[32..])
+            .map_err(|_| ProverError::InvalidMemo)?;
+        Ok(WithdrawNote {
+            nullifier: plaintext[..32].try_into().unwrap(),
+            secret: plaintext[32..].try_into().unwrap(),
+        })
+    }
+
+    /// Every note of `events` encrypted to this key. A memo is only trusted if its
+    /// note hashes to the commitment that was actually inserted.
+    pub fn scan<'a>(
+        &self,
+        events: impl IntoIterator<Item = &'a DepositEvent>,
+    ) -> Vec<RecoveredNote> {
+        events
+            .into_iter()
+            .filter_map(|event| {
+                let note = self.decrypt(event.memo.as_deref()?).ok()?;
+                (note.commitment() == event.commitment).then_some(RecoveredNote {
+                    merkle: event.merkle,
+                    index: event.index,
+                    note,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Encrypts `note` to `viewing_key` under a fresh ephemeral key, the result goes in
+/// `DepositArgs::memo`.
+pub fn encrypt_note(viewing_key: &[u8; 32], note: &WithdrawNote) -> Vec<u8> {
+    let ephemeral = StaticSecret::random();
+    let ephemeral_public = PublicKey::from(&ephemeral).to_bytes();
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(*viewing_key));
+    let plaintext = [note.nullifier, note.secret].concat();
+    let ciphertext = cipher(shared.as_bytes(), &ephemeral_public, viewing_key)
+        .encrypt(&Nonce::default(), plaintext.as_slice())
+        .expect("encrypting a 64 byte note can't fail");
+    [ephemeral_public.as_slice(), &ciphertext].concat()
+}
+
+/// `DepositEvent`s in the logs of one or more transactions, other lines are skipped.
+pub fn deposit_events<S: AsRef<str>>(logs: &[S]) -> Vec<DepositEvent> {
+    logs.iter()
+        .filter_map(|line| line.as_ref().strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter_map(|bytes| {
+            let data = bytes.strip_prefix(DepositEvent::DISCRIMINATOR.as_slice())?;
+            DepositEvent::deserialize(&mut &data[..]).ok()
+        })
+        .collect()
+}
+
+/// Every memo gets its own ephemeral key, so its key is used once and a zero nonce
+/// is safe.
+fn cipher(shared: &[u8; 32], ephemeral: &[u8; 32], viewing_key: &[u8; 32]) -> ChaCha20Poly1305 {
+    let key = Sha256::new()
+        .chain_update(DOMAIN)
+        .chain_update(shared)
+        .chain_update(ephemeral)
+        .chain_update(viewing_key)
+        .finalize();
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
@@ -0,0 +1,127 @@
+use crate::artifacts::CircuitArtifacts;
+use crate::error::ProverError;
+use crate::inputs::WithdrawInputs;
+
+use ark_bn254::{Bn254, Fr};
+use ark_circom::{read_zkey, CircomBuilder, CircomConfig, CircomReduction};
+use ark_groth16::{Groth16, Proof, ProvingKey};
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+use std::fs::File;
+use std::io::BufReader;
+use zklsol::instructions::WithdrawArgs;
+use zklsol::withdraw_verifier::ProofEncoding;
+use zklsol_snarkjs::layout::{
+    compress_proof_bytes, fr_to_be, proof_to_bytes, COMPRESSED_PROOF_SIZE, PROOF_SIZE,
+};
+
+/// Proof plus the public values it was generated for.
+#[derive(Debug, Clone)]
+pub struct WithdrawProof {
+    /// Uncompressed layout, see `layout`.
+    pub proof: [u8; PROOF_SIZE],
+    pub compressed_proof: [u8; COMPRESSED_PROOF_SIZE],
+    pub root: [u8; 32],
+    pub nullifier_hash: [u8; 32],
+    /// Public signals in circuit order: `recipientOut`, `nullifierHashOut`.
+    pub public_inputs: Vec<[u8; 32]>,
+}
+
+impl WithdrawProof {
+    /// Arguments carrying the compressed proof, the smaller transaction.
+    pub fn to_withdraw_args(&self) -> WithdrawArgs {
+        WithdrawArgs {
+            nullifier_hash: self.nullifier_hash,
+            proof: ProofEncoding::Compressed(self.compressed_proof),
+            root: self.root,
+        }
+    }
+
+    pub fn to_uncompressed_withdraw_args(&self) -> WithdrawArgs {
+        WithdrawArgs {
+            nullifier_hash: self.nullifier_hash,
+            proof: ProofEncoding::Uncompressed(self.proof),
+            root: self.root,
+        }
+    }
+}
+
+pub struct WithdrawProver {
+    pub depth: u8,
+    pub artifacts: CircuitArtifacts,
+    pub proving_key: ProvingKey<Bn254>,
+}
+
+impl WithdrawProver {
+    /// Loads `withdrawN_final.zkey` for a pool of `depth` from `circuits-output`.
+    pub fn load(artifacts: CircuitArtifacts, depth: u8) -> Result<Self, ProverError> {
+        artifacts.check_exists()?;
+        let mut reader = BufReader::new(File::open(&artifacts.zkey)?);
+        let (proving_key, _matrices) =
+            read_zkey(&mut reader).map_err(|e| ProverError::ReadZkey(e.to_string()))?;
+        Ok(Self {
+            depth,
+            artifacts,
+            proving_key,
+        })
+    }
+
+    /// Number of path elements the circuit expects, the root level isn't counted.
+    pub fn levels(&self) -> usize {
+        self.depth.saturating_sub(1) as usize
+    }
+
+    pub fn prove<R: RngCore + CryptoRng>(
+        &self,
+        inputs: &WithdrawInputs,
+        rng: &mut R,
+    ) -> Result<WithdrawProof, ProverError> {
+        inputs.validate(self.levels())?;
+        // The witness calculator runs on wasmer's WASI environment, which needs a tokio runtime.
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let _runtime = runtime.enter();
+        let config = CircomConfig::<Fr>::new(&self.artifacts.wasm, &self.artifacts.r1cs)
+            .map_err(|e| ProverError::Witness(e.to_string()))?;
+        let mut builder = CircomBuilder::new(config);
+        for (name, values) in inputs.signals() {
+            for value in values {
+                builder.push_input(name, value);
+            }
+        }
+        let circuit = builder
+            .build()
+            .map_err(|e| ProverError::Witness(e.to_string()))?;
+        let public_signals = circuit
+            .get_public_inputs()
+            .ok_or_else(|| ProverError::Witness("witness has no public inputs".to_string()))?;
+
+        let proof: Proof<Bn254> =
+            Groth16::<Bn254, CircomReduction>::prove(&self.proving_key, circuit, rng)
+                .map_err(|e| ProverError::Prove(e.to_string()))?;
+        let verified = Groth16::<Bn254, CircomReduction>::verify(
+            &self.proving_key.vk,
+            &public_signals,
+            &proof,
+        )
+        .map_err(|e| ProverError::Prove(e.to_string()))?;
+        if !verified {
+            return Err(ProverError::InvalidProof);
+        }
+
+        let public_inputs: Vec<[u8; 32]> = public_signals.iter().map(fr_to_be).collect();
+        let nullifier_hash = inputs.note.nullifier_hash();
+        if public_inputs != vec![inputs.recipient_signal(), nullifier_hash] {
+            return Err(ProverError::PublicSignalsMismatch);
+        }
+        let proof = proof_to_bytes(&proof);
+        Ok(WithdrawProof {
+            proof,
+            compressed_proof: compress_proof_bytes(&proof)?,
+            root: inputs.root,
+            nullifier_hash,
+            public_inputs,
+        })
+    }
+}
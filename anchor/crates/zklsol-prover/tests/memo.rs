@@ -0,0 +1,102 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use zklsol::events::{DepositEvent, MAX_NOTE_MEMO_SIZE};
+use zklsol_prover::memo::{deposit_events, encrypt_note, NOTE_MEMO_SIZE};
+use zklsol_prover::{ProverError, ViewingKey, WithdrawNote};
+
+fn note(i: u8) -> WithdrawNote {
+    WithdrawNote {
+        nullifier: [i; 32],
+        secret: [i + 1; 32],
+    }
+}
+
+fn event(index: u64, note: &WithdrawNote, memo: Option<Vec<u8>>) -> DepositEvent {
+    DepositEvent {
+        merkle: Pubkey::new_from_array([9u8; 32]),
+        index,
+        commitment: note.commitment(),
+        memo,
+    }
+}
+
+#[test]
+fn memo_roundtrip_test() {
+    let key = ViewingKey::random();
+    let memo = encrypt_note(&key.public_key(), &note(1));
+    assert_eq!(memo.len(), NOTE_MEMO_SIZE);
+    assert!(memo.len() <= MAX_NOTE_MEMO_SIZE);
+    // Fresh ephemeral keys, the same note never encrypts to the same memo.
+    assert_ne!(encrypt_note(&key.public_key(), &note(1)), memo);
+
+    let decrypted = key.decrypt(&memo).unwrap();
+    assert_eq!(decrypted.nullifier, note(1).nullifier);
+    assert_eq!(decrypted.secret, note(1).secret);
+    let restored = ViewingKey::from_bytes(key.to_bytes());
+    assert_eq!(restored.decrypt(&memo).unwrap().secret, note(1).secret);
+
+    assert!(matches!(
+        ViewingKey::random().decrypt(&memo),
+        Err(ProverError::InvalidMemo)
+    ));
+    let mut tampered = memo.clone();
+    tampered[40] ^= 1;
+    assert!(matches!(
+        key.decrypt(&tampered),
+        Err(ProverError::InvalidMemo)
+    ));
+    assert!(matches!(
+        key.decrypt(&memo[..NOTE_MEMO_SIZE - 1]),
+        Err(ProverError::InvalidMemo)
+    ));
+}
+
+#[test]
+fn scan_recovers_own_notes_test() {
+    let key = ViewingKey::random();
+    let other = ViewingKey::random();
+    let events = [
+        event(0, &note(1), Some(encrypt_note(&key.public_key(), &note(1)))),
+        event(1, &note(3), None),
+        event(
+            2,
+            &note(5),
+            Some(encrypt_note(&other.public_key(), &note(5))),
+        ),
+        event(3, &note(7), Some(encrypt_note(&key.public_key(), &note(7)))),
+        // A memo that decrypts but doesn't match the inserted leaf is ignored.
+        event(
+            4,
+            &note(9),
+            Some(encrypt_note(&key.public_key(), &note(11))),
+        ),
+    ];
+    let recovered = key.scan(&events);
+    assert_eq!(
+        recovered.iter().map(|r| r.index).collect::<Vec<_>>(),
+        vec![0, 3]
+    );
+    assert_eq!(recovered[1].note.nullifier, note(7).nullifier);
+    assert_eq!(recovered[1].merkle, Pubkey::new_from_array([9u8; 32]));
+    assert_eq!(other.scan(&events).len(), 1);
+}
+
+#[test]
+fn deposit_events_from_logs_test() {
+    let key = ViewingKey::random();
+    let deposit = event(5, &note(1), Some(encrypt_note(&key.public_key(), &note(1))));
+    let logs = vec![
+        "Program 4BEBe7TVDef5Nfdft252mMCSNBBmPLQ2gVXmKvJvSbP1 invoke [1]".to_string(),
+        "Program log: pending_proof.index = 5".to_string(),
+        format!("Program data: {}", STANDARD.encode(deposit.data())),
+        format!("Program data: {}", STANDARD.encode([0u8; 16])),
+        "Program 4BEBe7TVDef5Nfdft252mMCSNBBmPLQ2gVXmKvJvSbP1 success".to_string(),
+    ];
+    let events = deposit_events(&logs);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].index, 5);
+    assert_eq!(events[0].commitment, note(1).commitment());
+    assert_eq!(key.scan(&events)[0].index, 5);
+}
@@ -0,0 +1,156 @@
+use anchor_lang::prelude::Pubkey;
+use ark_std::rand::rngs::StdRng;
+use ark_std::rand::SeedableRng;
+use std::path::PathBuf;
+use zklsol::error::ErrorCodes;
+use zklsol::preflight::{preflight_withdraw, CheckStatus, PreflightInput};
+use zklsol::state::merkle_state::MerkleState;
+use zklsol::state::merkle_tree::MerkleTree;
+use zklsol::state::merkle_zeros::MerkleZeros;
+use zklsol::withdraw_verifier::ProofEncoding;
+use zklsol_prover::layout::{decompress_proof_bytes, proof_from_bytes, proof_to_bytes};
+use zklsol_prover::{CircuitArtifacts, ProverError, WithdrawInputs, WithdrawNote, WithdrawProver};
+use zklsol_snarkjs::json::be_to_decimal;
+use zklsol_snarkjs::withdraw::{proof_json, withdraw_args};
+
+const DEPTH: u8 = 3;
+
+fn circuits_output() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../circuits-output")
+}
+
+fn note() -> WithdrawNote {
+    let mut nullifier = [0u8; 32];
+    nullifier[31] = 45;
+    let mut secret = [0u8; 32];
+    secret[31] = 123;
+    WithdrawNote { nullifier, secret }
+}
+
+/// Single deposit at leaf 0, so every sibling is an empty subtree.
+fn deposit() -> (MerkleTree, WithdrawInputs) {
+    let zeros = MerkleZeros::new(DEPTH, 0);
+    let mut tree = MerkleTree::new(DEPTH, &zeros).unwrap();
+    let note = note();
+    let proof = tree.insert(&note.commitment(), &zeros).unwrap();
+    let inputs = WithdrawInputs {
+        note,
+        root: proof.root().unwrap().hash,
+        recipient: Pubkey::new_from_array([7u8; 32]),
+        path_elements: (0..DEPTH - 1).map(|level| zeros.get(level)).collect(),
+        path_indices: vec![0; (DEPTH - 1) as usize],
+    };
+    (tree, inputs)
+}
+
+fn inputs() -> WithdrawInputs {
+    deposit().1
+}
+
+fn merkle_state(tree: MerkleTree) -> MerkleState {
+    let mut state = MerkleState::from_tree(Pubkey::new_unique(), 1, tree);
+    state.pin_verifying_key().unwrap();
+    state
+}
+
+#[test]
+fn withdraw3_prove_test() {
+    let prover =
+        WithdrawProver::load(CircuitArtifacts::withdraw(circuits_output(), DEPTH), DEPTH).unwrap();
+    let mut rng = StdRng::seed_from_u64(3);
+    let inputs = inputs();
+    let proof = prover.prove(&inputs, &mut rng).unwrap();
+    assert_eq!(proof.public_inputs[0], inputs.recipient_signal());
+    assert_eq!(proof.public_inputs[1], inputs.note.nullifier_hash());
+    let args = proof.to_withdraw_args();
+    assert_eq!(args.root, inputs.root);
+    assert_eq!(
+        args.proof,
+        ProofEncoding::Compressed(proof.compressed_proof)
+    );
+    assert_eq!(
+        decompress_proof_bytes(&proof.compressed_proof).unwrap(),
+        proof.proof
+    );
+    let decoded = proof_from_bytes(&proof.proof).unwrap();
+    assert_eq!(proof_to_bytes(&decoded), proof.proof);
+
+    // Same bytes as going through snarkjs' proof.json / public.json.
+    let public: Vec<String> = proof.public_inputs.iter().map(be_to_decimal).collect();
+    let converted = withdraw_args(
+        &proof_json(&proof.proof),
+        &public,
+        args.root,
+        Some(&inputs.recipient),
+        true,
+    )
+    .unwrap();
+    assert_eq!(converted.proof, args.proof);
+    assert_eq!(converted.nullifier_hash, args.nullifier_hash);
+}
+
+#[test]
+fn withdraw3_rejects_bad_path_test() {
+    let prover =
+        WithdrawProver::load(CircuitArtifacts::withdraw(circuits_output(), DEPTH), DEPTH).unwrap();
+    let mut rng = StdRng::seed_from_u64(4);
+    let mut inputs = inputs();
+    inputs.path_elements.pop();
+    assert!(matches!(
+        prover.prove(&inputs, &mut rng),
+        Err(ProverError::InvalidPathLength { .. })
+    ));
+    let mut inputs = self::inputs();
+    inputs.path_indices[0] = 2;
+    assert!(matches!(
+        prover.prove(&inputs, &mut rng),
+        Err(ProverError::InvalidPathIndex(2))
+    ));
+    let mut inputs = self::inputs();
+    inputs.root = [1u8; 32];
+    assert!(matches!(
+        prover.prove(&inputs, &mut rng),
+        Err(ProverError::RootMismatch)
+    ));
+    let mut inputs = self::inputs();
+    inputs.recipient = Pubkey::new_from_array([0xff; 32]);
+    assert!(matches!(
+        prover.prove(&inputs, &mut rng),
+        Err(ProverError::InvalidRecipient)
+    ));
+}
+
+#[test]
+fn withdraw3_preflight_test() {
+    let prover =
+        WithdrawProver::load(CircuitArtifacts::withdraw(circuits_output(), DEPTH), DEPTH).unwrap();
+    let mut rng = StdRng::seed_from_u64(5);
+    let (tree, inputs) = deposit();
+    let merkle = merkle_state(tree);
+    let proof = prover.prove(&inputs, &mut rng).unwrap();
+    let args = proof.to_withdraw_args();
+    let signer = Pubkey::new_unique();
+    for args in [&args, &proof.to_uncompressed_withdraw_args()] {
+        let report = preflight_withdraw(&PreflightInput {
+            args,
+            signer: &signer,
+            recipient: &inputs.recipient,
+            merkle: &merkle,
+            nullifier_account_exists: false,
+        });
+        assert!(report.is_ok(), "{:?}", report);
+    }
+
+    let other_recipient = Pubkey::new_from_array([8u8; 32]);
+    let report = preflight_withdraw(&PreflightInput {
+        args: &args,
+        signer: &signer,
+        recipient: &other_recipient,
+        merkle: &merkle,
+        nullifier_account_exists: false,
+    });
+    assert!(matches!(
+        report.verification,
+        CheckStatus::Failed(ErrorCodes::Groth16VerifyError)
+    ));
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::Pubkey;
+use std::fmt::Write;
+use std::fs;
+use std::process;
+use std::str::FromStr;
+use zklsol::withdraw_verifier::plonk_key_hash;
+use zklsol_snarkjs::json::{parse_field_element, protocol_of};
+use zklsol_snarkjs::plonk::{plonk_verifying_key_from_json_str, plonk_vk_to_rust_source, PLONK};
+use zklsol_snarkjs::withdraw::{withdraw_args_bytes, withdraw_args_from_json};
+use zklsol_snarkjs::{ConvertError, VerifyingKeyBytes};
+
+const USAGE: &str = "usage:
+  zklsol-snarkjs vk-to-rs <verification_key.json> [output.rs]
+  zklsol-snarkjs plonk-vk-to-rs <verification_key.json> [output.rs]
+  zklsol-snarkjs vk-to-account <verification_key.json> <output.bin>
+  zklsol-snarkjs check-vk <verification_key.json> <verifying_key.rs>
+  zklsol-snarkjs vk-hash <verification_key.json>
+  zklsol-snarkjs proof-to-args [--uncompressed] <proof.json> <public.json> <root> [recipient] [output.bin]";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+fn run(args: &[String]) -> Result<(), ConvertError> {
+    let uncompressed = args.iter().any(|a| a == "--uncompressed");
+    let args: Vec<String> = args
+        .iter()
+        .filter(|a| *a != "--uncompressed")
+        .cloned()
+        .collect();
+    match args.as_slice() {
+        [command, vk] | [command, vk, _] if command == "vk-to-rs" => {
+            let source =
+                VerifyingKeyBytes::from_json_str(&fs::read_to_string(vk)?)?.to_rust_source();
+            match args.get(2) {
+                Some(output) => fs::write(output, source)?,
+                None => println!("{}", source),
+            }
+        }
+        [command, vk] | [command, vk, _] if command == "plonk-vk-to-rs" => {
+            let source = plonk_vk_to_rust_source(&plonk_verifying_key_from_json_str(
+                &fs::read_to_string(vk)?,
+            )?);
+            match args.get(2) {
+                Some(output) => fs::write(output, source)?,
+                None => println!("{}", source),
+            }
+        }
+        [command, vk, output] if command == "vk-to-account" => {
+            let key = VerifyingKeyBytes::from_json_str(&fs::read_to_string(vk)?)?;
+            fs::write(output, key.to_account_data())?;
+        }
+        [command, vk, source] if command == "check-vk" => {
+            let expected = VerifyingKeyBytes::from_json_str(&fs::read_to_string(vk)?)?;
+            let committed = VerifyingKeyBytes::from_rust_source(&fs::read_to_string(source)?)?;
+            if expected != committed {
+                return Err(ConvertError::InvalidSource(format!(
+                    "{} doesn't match {}",
+                    source, vk
+                )));
+            }
+            println!("{} matches {}", source, vk);
+        }
+        [command, vk] if command == "vk-hash" => {
+            let json = fs::read_to_string(vk)?;
+            let hash = if protocol_of(&json)? == PLONK {
+                plonk_key_hash(&plonk_verifying_key_from_json_str(&json)?)
+            } else {
+                VerifyingKeyBytes::from_json_str(&json)?.hash()
+            };
+            println!("{}", hex(&hash));
+        }
+        [command, proof, public, root, rest @ ..]
+            if command == "proof-to-args" && rest.len() <= 2 =>
+        {
+            let recipient = rest
+                .first()
+                .map(|r| Pubkey::from_str(r))
+                .transpose()
+                .map_err(|e| ConvertError::InvalidNumber(e.to_string()))?;
+            let withdraw_args = withdraw_args_from_json(
+                &fs::read_to_string(proof)?,
+                &fs::read_to_string(public)?,
+                parse_field_element(root)?,
+                recipient.as_ref(),
+                !uncompressed,
+            )?;
+            let bytes = withdraw_args_bytes(&withdraw_args);
+            match rest.get(1) {
+                Some(output) => fs::write(output, bytes)?,
+                None => println!("{}", hex(&bytes)),
+            }
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
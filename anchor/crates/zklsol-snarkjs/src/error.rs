@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported protocol {0}")]
+    UnsupportedProtocol(String),
+    #[error("unsupported curve {0}, expected bn128")]
+    UnsupportedCurve(String),
+    #[error("invalid number {0}")]
+    InvalidNumber(String),
+    #[error("value {0} is not a canonical field element")]
+    NotInField(String),
+    #[error("invalid point encoding: {0}")]
+    InvalidPoint(String),
+    #[error("expected {expected} public signals, got {actual}")]
+    PublicSignalsLength { expected: usize, actual: usize },
+    #[error("public signal {0} doesn't match")]
+    PublicSignalMismatch(&'static str),
+    #[error("invalid verifying key source: {0}")]
+    InvalidSource(String),
+    #[error("invalid account data: {0}")]
+    InvalidAccountData(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,136 @@
+//! snarkjs JSON artifacts. Numbers are decimal strings, points are projective with `z == 1`.
+use crate::error::ConvertError;
+use crate::layout::{fr_from_be, fr_to_be, g1_from_bytes, g2_from_bytes};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationKeyJson {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub vk_alpha_1: Vec<String>,
+    pub vk_beta_2: Vec<Vec<String>>,
+    pub vk_gamma_2: Vec<Vec<String>>,
+    pub vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    pub ic: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofJson {
+    pub pi_a: Vec<String>,
+    pub pi_b: Vec<Vec<String>>,
+    pub pi_c: Vec<String>,
+    pub protocol: String,
+    pub curve: String,
+}
+
+pub fn check_protocol(protocol: &str, curve: &str) -> Result<(), ConvertError> {
+    expect_protocol("groth16", protocol, curve)
+}
+
+pub fn expect_protocol(expected: &str, protocol: &str, curve: &str) -> Result<(), ConvertError> {
+    if protocol != expected {
+        return Err(ConvertError::UnsupportedProtocol(protocol.to_string()));
+    }
+    if curve != "bn128" {
+        return Err(ConvertError::UnsupportedCurve(curve.to_string()));
+    }
+    Ok(())
+}
+
+pub fn decimal_to_be(value: &str) -> Result<[u8; 32], ConvertError> {
+    let number = BigUint::parse_bytes(value.trim().as_bytes(), 10)
+        .ok_or_else(|| ConvertError::InvalidNumber(value.to_string()))?;
+    let bytes = number.to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(ConvertError::NotInField(value.to_string()));
+    }
+    let mut output = [0u8; 32];
+    output[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(output)
+}
+
+pub fn be_to_decimal(bytes: &[u8; 32]) -> String {
+    BigUint::from_bytes_be(bytes).to_str_radix(10)
+}
+
+/// `protocol` field of a snarkjs proof or verification key, to pick the parser.
+pub fn protocol_of(json: &str) -> Result<String, ConvertError> {
+    #[derive(Deserialize)]
+    struct Protocol {
+        protocol: String,
+    }
+    Ok(serde_json::from_str::<Protocol>(json)?.protocol)
+}
+
+/// Accepts a decimal string, as snarkjs prints them, or `0x` prefixed hex.
+pub fn parse_field_element(value: &str) -> Result<[u8; 32], ConvertError> {
+    let bytes = match value.strip_prefix("0x") {
+        Some(hex) => {
+            let number = BigUint::parse_bytes(hex.as_bytes(), 16)
+                .ok_or_else(|| ConvertError::InvalidNumber(value.to_string()))?;
+            decimal_to_be(&number.to_str_radix(10))?
+        }
+        None => decimal_to_be(value)?,
+    };
+    if fr_to_be(&fr_from_be(&bytes)) != bytes {
+        return Err(ConvertError::NotInField(value.to_string()));
+    }
+    Ok(bytes)
+}
+
+fn check_affine(point: &[String], one: &[&str]) -> Result<(), ConvertError> {
+    if point.len() != 3 || point[2..].iter().zip(one).any(|(a, b)| a != b) {
+        return Err(ConvertError::InvalidPoint(format!("{:?}", point)));
+    }
+    Ok(())
+}
+
+/// `[x, y, "1"]` to `x || y`, big-endian.
+pub fn g1_from_json(point: &[String]) -> Result<[u8; 64], ConvertError> {
+    check_affine(point, &["1"])?;
+    let mut output = [0u8; 64];
+    output[..32].copy_from_slice(&decimal_to_be(&point[0])?);
+    output[32..].copy_from_slice(&decimal_to_be(&point[1])?);
+    let _ = g1_from_bytes(&output)?;
+    Ok(output)
+}
+
+/// `[[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]` to `x.c1 || x.c0 || y.c1 || y.c0`, big-endian.
+pub fn g2_from_json(point: &[Vec<String>]) -> Result<[u8; 128], ConvertError> {
+    if point.len() != 3
+        || point.iter().any(|c| c.len() != 2)
+        || point[2][0] != "1"
+        || point[2][1] != "0"
+    {
+        return Err(ConvertError::InvalidPoint(format!("{:?}", point)));
+    }
+    let mut output = [0u8; 128];
+    output[..32].copy_from_slice(&decimal_to_be(&point[0][1])?);
+    output[32..64].copy_from_slice(&decimal_to_be(&point[0][0])?);
+    output[64..96].copy_from_slice(&decimal_to_be(&point[1][1])?);
+    output[96..].copy_from_slice(&decimal_to_be(&point[1][0])?);
+    let _ = g2_from_bytes(&output)?;
+    Ok(output)
+}
+
+pub fn g1_to_json(bytes: &[u8]) -> Vec<String> {
+    vec![
+        be_to_decimal(bytes[..32].try_into().unwrap()),
+        be_to_decimal(bytes[32..64].try_into().unwrap()),
+        "1".to_string(),
+    ]
+}
+
+pub fn g2_to_json(bytes: &[u8]) -> Vec<Vec<String>> {
+    let coordinate =
+        |range: std::ops::Range<usize>| be_to_decimal(bytes[range].try_into().unwrap());
+    vec![
+        vec![coordinate(32..64), coordinate(0..32)],
+        vec![coordinate(96..128), coordinate(64..96)],
+        vec!["1".to_string(), "0".to_string()],
+    ]
+}
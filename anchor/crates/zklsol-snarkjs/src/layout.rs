@@ -0,0 +1,116 @@
+//! Byte layout of `WithdrawArgs.proof`, matching `ZkHelper.convertProofToBytes`:
+//! `proof_a` (x, y), `proof_b` (x.c1, x.c0, y.c1, y.c0) and `proof_c` (x, y),
+//! every coordinate 32 bytes big-endian. `proof_a` is not negated, `withdraw` does that on-chain.
+//! The compressed form is the 128 byte `ProofEncoding::Compressed` payload.
+use crate::error::ConvertError;
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::Proof;
+use zklsol::withdraw_verifier::{compress_proof, decompress_proof, ProofEncoding};
+
+pub use zklsol::withdraw_verifier::{COMPRESSED_PROOF_SIZE, PROOF_SIZE};
+
+pub fn fq_to_be(value: &Fq) -> [u8; 32] {
+    to_array(&value.into_bigint().to_bytes_be())
+}
+
+pub fn fr_to_be(value: &Fr) -> [u8; 32] {
+    to_array(&value.into_bigint().to_bytes_be())
+}
+
+pub fn fr_from_be(bytes: &[u8; 32]) -> Fr {
+    Fr::from_be_bytes_mod_order(bytes)
+}
+
+fn to_array(bytes: &[u8]) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    output[32 - bytes.len()..].copy_from_slice(bytes);
+    output
+}
+
+fn fq_from_be(bytes: &[u8]) -> Result<Fq, ConvertError> {
+    let value = Fq::from_be_bytes_mod_order(bytes);
+    if fq_to_be(&value) != bytes {
+        return Err(ConvertError::InvalidPoint(
+            "coordinate is not a canonical field element".to_string(),
+        ));
+    }
+    Ok(value)
+}
+
+pub fn g1_to_bytes(point: &G1Affine) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    output[..32].copy_from_slice(&fq_to_be(&point.x));
+    output[32..].copy_from_slice(&fq_to_be(&point.y));
+    output
+}
+
+pub fn g2_to_bytes(point: &G2Affine) -> [u8; 128] {
+    let mut output = [0u8; 128];
+    output[..32].copy_from_slice(&fq_to_be(&point.x.c1));
+    output[32..64].copy_from_slice(&fq_to_be(&point.x.c0));
+    output[64..96].copy_from_slice(&fq_to_be(&point.y.c1));
+    output[96..].copy_from_slice(&fq_to_be(&point.y.c0));
+    output
+}
+
+pub fn g1_from_bytes(bytes: &[u8]) -> Result<G1Affine, ConvertError> {
+    let point = G1Affine::new_unchecked(fq_from_be(&bytes[..32])?, fq_from_be(&bytes[32..64])?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ConvertError::InvalidPoint(
+            "G1 point not on curve".to_string(),
+        ));
+    }
+    Ok(point)
+}
+
+pub fn g2_from_bytes(bytes: &[u8]) -> Result<G2Affine, ConvertError> {
+    let x = Fq2::new(fq_from_be(&bytes[32..64])?, fq_from_be(&bytes[..32])?);
+    let y = Fq2::new(fq_from_be(&bytes[96..128])?, fq_from_be(&bytes[64..96])?);
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ConvertError::InvalidPoint(
+            "G2 point not on curve".to_string(),
+        ));
+    }
+    Ok(point)
+}
+
+pub fn proof_to_bytes(proof: &Proof<Bn254>) -> [u8; PROOF_SIZE] {
+    let mut output = [0u8; PROOF_SIZE];
+    output[..64].copy_from_slice(&g1_to_bytes(&proof.a));
+    output[64..192].copy_from_slice(&g2_to_bytes(&proof.b));
+    output[192..].copy_from_slice(&g1_to_bytes(&proof.c));
+    output
+}
+
+pub fn proof_from_bytes(bytes: &[u8; PROOF_SIZE]) -> Result<Proof<Bn254>, ConvertError> {
+    Ok(Proof {
+        a: g1_from_bytes(&bytes[..64])?,
+        b: g2_from_bytes(&bytes[64..192])?,
+        c: g1_from_bytes(&bytes[192..])?,
+    })
+}
+
+pub fn compress_proof_bytes(
+    bytes: &[u8; PROOF_SIZE],
+) -> Result<[u8; COMPRESSED_PROOF_SIZE], ConvertError> {
+    compress_proof(bytes).map_err(|e| ConvertError::InvalidPoint(e.to_string()))
+}
+
+pub fn decompress_proof_bytes(
+    bytes: &[u8; COMPRESSED_PROOF_SIZE],
+) -> Result<[u8; PROOF_SIZE], ConvertError> {
+    decompress_proof(bytes).map_err(|e| ConvertError::InvalidPoint(e.to_string()))
+}
+
+pub fn proof_encoding(
+    bytes: &[u8; PROOF_SIZE],
+    compressed: bool,
+) -> Result<ProofEncoding, ConvertError> {
+    if compressed {
+        Ok(ProofEncoding::Compressed(compress_proof_bytes(bytes)?))
+    } else {
+        Ok(ProofEncoding::Uncompressed(*bytes))
+    }
+}
@@ -0,0 +1,10 @@
+pub mod error;
+pub mod json;
+pub mod layout;
+pub mod plonk;
+pub mod verifying_key;
+pub mod withdraw;
+
+pub use error::ConvertError;
+pub use json::{ProofJson, VerificationKeyJson};
+pub use verifying_key::VerifyingKeyBytes;
@@ -0,0 +1,195 @@
+//! `snarkjs plonk setup` / `snarkjs plonk prove` artifacts to `PlonkVerifyingKey`,
+//! its Rust module and `ProofEncoding::Plonk`.
+use crate::error::ConvertError;
+use crate::json::{
+    be_to_decimal, expect_protocol, g1_from_json, g1_to_json, g2_from_json, parse_field_element,
+};
+use crate::withdraw::withdraw_signals;
+use anchor_lang::prelude::Pubkey;
+use serde::{Deserialize, Serialize};
+use zklsol::instructions::WithdrawArgs;
+use zklsol::plonk_verifier::{PlonkProof, PlonkVerifyingKey, PLONK_PROOF_SIZE};
+use zklsol::withdraw_verifier::ProofEncoding;
+
+pub const PLONK: &str = "plonk";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlonkVerificationKeyJson {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub power: u32,
+    pub k1: String,
+    pub k2: String,
+    #[serde(rename = "Qm")]
+    pub qm: Vec<String>,
+    #[serde(rename = "Ql")]
+    pub ql: Vec<String>,
+    #[serde(rename = "Qr")]
+    pub qr: Vec<String>,
+    #[serde(rename = "Qo")]
+    pub qo: Vec<String>,
+    #[serde(rename = "Qc")]
+    pub qc: Vec<String>,
+    #[serde(rename = "S1")]
+    pub s1: Vec<String>,
+    #[serde(rename = "S2")]
+    pub s2: Vec<String>,
+    #[serde(rename = "S3")]
+    pub s3: Vec<String>,
+    #[serde(rename = "X_2")]
+    pub x_2: Vec<Vec<String>>,
+    pub w: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlonkProofJson {
+    #[serde(rename = "A")]
+    pub a: Vec<String>,
+    #[serde(rename = "B")]
+    pub b: Vec<String>,
+    #[serde(rename = "C")]
+    pub c: Vec<String>,
+    #[serde(rename = "Z")]
+    pub z: Vec<String>,
+    #[serde(rename = "T1")]
+    pub t1: Vec<String>,
+    #[serde(rename = "T2")]
+    pub t2: Vec<String>,
+    #[serde(rename = "T3")]
+    pub t3: Vec<String>,
+    #[serde(rename = "Wxi")]
+    pub wxi: Vec<String>,
+    #[serde(rename = "Wxiw")]
+    pub wxiw: Vec<String>,
+    pub eval_a: String,
+    pub eval_b: String,
+    pub eval_c: String,
+    pub eval_s1: String,
+    pub eval_s2: String,
+    pub eval_zw: String,
+    pub protocol: String,
+    pub curve: String,
+}
+
+pub fn plonk_verifying_key(
+    vk: &PlonkVerificationKeyJson,
+) -> Result<PlonkVerifyingKey, ConvertError> {
+    expect_protocol(PLONK, &vk.protocol, &vk.curve)?;
+    Ok(PlonkVerifyingKey {
+        n_public: vk.n_public,
+        power: vk.power,
+        k1: parse_field_element(&vk.k1)?,
+        k2: parse_field_element(&vk.k2)?,
+        w: parse_field_element(&vk.w)?,
+        qm: g1_from_json(&vk.qm)?,
+        ql: g1_from_json(&vk.ql)?,
+        qr: g1_from_json(&vk.qr)?,
+        qo: g1_from_json(&vk.qo)?,
+        qc: g1_from_json(&vk.qc)?,
+        s1: g1_from_json(&vk.s1)?,
+        s2: g1_from_json(&vk.s2)?,
+        s3: g1_from_json(&vk.s3)?,
+        x_2: g2_from_json(&vk.x_2)?,
+    })
+}
+
+pub fn plonk_verifying_key_from_json_str(json: &str) -> Result<PlonkVerifyingKey, ConvertError> {
+    plonk_verifying_key(&serde_json::from_str(json)?)
+}
+
+/// Module to place next to the Groth16 keys in `programs/zklsol/src`,
+/// laid out like the ones `VerifyingKeyBytes::to_rust_source` emits.
+pub fn plonk_vk_to_rust_source(vk: &PlonkVerifyingKey) -> String {
+    let mut s = format!(
+        "use crate::plonk_verifier::PlonkVerifyingKey;\n\npub const VERIFYINGKEY: PlonkVerifyingKey = PlonkVerifyingKey {{\n\tn_public: {},\n\tpower: {},\n\n",
+        vk.n_public, vk.power
+    );
+    for (name, bytes) in [
+        ("k1", &vk.k1[..]),
+        ("k2", &vk.k2[..]),
+        ("w", &vk.w[..]),
+        ("qm", &vk.qm[..]),
+        ("ql", &vk.ql[..]),
+        ("qr", &vk.qr[..]),
+        ("qo", &vk.qo[..]),
+        ("qc", &vk.qc[..]),
+        ("s1", &vk.s1[..]),
+        ("s2", &vk.s2[..]),
+        ("s3", &vk.s3[..]),
+        ("x_2", &vk.x_2[..]),
+    ] {
+        s.push_str(&format!("\t{}: [\n", name));
+        for row in bytes.chunks(32) {
+            s.push_str("\t\t");
+            for byte in row {
+                s.push_str(&format!("{},", byte));
+            }
+            s.push('\n');
+        }
+        s.push_str("\t],\n");
+    }
+    s.push_str("};");
+    s
+}
+
+pub fn plonk_proof_bytes(proof: &PlonkProofJson) -> Result<[u8; PLONK_PROOF_SIZE], ConvertError> {
+    expect_protocol(PLONK, &proof.protocol, &proof.curve)?;
+    let proof = PlonkProof {
+        a: g1_from_json(&proof.a)?,
+        b: g1_from_json(&proof.b)?,
+        c: g1_from_json(&proof.c)?,
+        z: g1_from_json(&proof.z)?,
+        t1: g1_from_json(&proof.t1)?,
+        t2: g1_from_json(&proof.t2)?,
+        t3: g1_from_json(&proof.t3)?,
+        wxi: g1_from_json(&proof.wxi)?,
+        wxiw: g1_from_json(&proof.wxiw)?,
+        eval_a: parse_field_element(&proof.eval_a)?,
+        eval_b: parse_field_element(&proof.eval_b)?,
+        eval_c: parse_field_element(&proof.eval_c)?,
+        eval_s1: parse_field_element(&proof.eval_s1)?,
+        eval_s2: parse_field_element(&proof.eval_s2)?,
+        eval_zw: parse_field_element(&proof.eval_zw)?,
+    };
+    Ok(proof.to_bytes())
+}
+
+pub fn plonk_proof_json(bytes: &[u8; PLONK_PROOF_SIZE]) -> PlonkProofJson {
+    let proof = PlonkProof::from_bytes(bytes);
+    PlonkProofJson {
+        a: g1_to_json(&proof.a),
+        b: g1_to_json(&proof.b),
+        c: g1_to_json(&proof.c),
+        z: g1_to_json(&proof.z),
+        t1: g1_to_json(&proof.t1),
+        t2: g1_to_json(&proof.t2),
+        t3: g1_to_json(&proof.t3),
+        wxi: g1_to_json(&proof.wxi),
+        wxiw: g1_to_json(&proof.wxiw),
+        eval_a: be_to_decimal(&proof.eval_a),
+        eval_b: be_to_decimal(&proof.eval_b),
+        eval_c: be_to_decimal(&proof.eval_c),
+        eval_s1: be_to_decimal(&proof.eval_s1),
+        eval_s2: be_to_decimal(&proof.eval_s2),
+        eval_zw: be_to_decimal(&proof.eval_zw),
+        protocol: PLONK.to_string(),
+        curve: "bn128".to_string(),
+    }
+}
+
+/// Same checks as `withdraw::withdraw_args`, for a pool created with `ProofSystem::Plonk`.
+pub fn plonk_withdraw_args(
+    proof: &PlonkProofJson,
+    public: &[String],
+    root: [u8; 32],
+    recipient: Option<&Pubkey>,
+) -> Result<WithdrawArgs, ConvertError> {
+    let nullifier_hash = withdraw_signals(public, recipient)?;
+    Ok(WithdrawArgs {
+        nullifier_hash,
+        proof: ProofEncoding::Plonk(Box::new(plonk_proof_bytes(proof)?)),
+        root,
+    })
+}
@@ -0,0 +1,180 @@
+//! `verification_key.json` to the `Groth16Verifyingkey` layout used by `groth16_solana`,
+//! either as Rust source (what `scripts/parse-vk-to-rust.ts` used to emit) or as account data.
+use crate::error::ConvertError;
+use crate::json::{check_protocol, g1_from_json, g2_from_json, VerificationKeyJson};
+use borsh::{BorshDeserialize, BorshSerialize};
+use zklsol::withdraw_verifier::groth16_key_hash;
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct VerifyingKeyBytes {
+    pub nr_pubinputs: u64,
+    pub vk_alpha_g1: [u8; 64],
+    pub vk_beta_g2: [u8; 128],
+    pub vk_gamme_g2: [u8; 128],
+    pub vk_delta_g2: [u8; 128],
+    pub vk_ic: Vec<[u8; 64]>,
+}
+
+impl VerifyingKeyBytes {
+    pub fn from_json(vk: &VerificationKeyJson) -> Result<Self, ConvertError> {
+        check_protocol(&vk.protocol, &vk.curve)?;
+        if vk.ic.len() != vk.n_public + 1 {
+            return Err(ConvertError::PublicSignalsLength {
+                expected: vk.n_public + 1,
+                actual: vk.ic.len(),
+            });
+        }
+        Ok(Self {
+            nr_pubinputs: vk.ic.len() as u64,
+            vk_alpha_g1: g1_from_json(&vk.vk_alpha_1)?,
+            vk_beta_g2: g2_from_json(&vk.vk_beta_2)?,
+            vk_gamme_g2: g2_from_json(&vk.vk_gamma_2)?,
+            vk_delta_g2: g2_from_json(&vk.vk_delta_2)?,
+            vk_ic: vk
+                .ic
+                .iter()
+                .map(|point| g1_from_json(point))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+
+    pub fn from_json_str(json: &str) -> Result<Self, ConvertError> {
+        Self::from_json(&serde_json::from_str(json)?)
+    }
+
+    /// Byte for byte the module committed under `circuits-output/*_rs`.
+    pub fn to_rust_source(&self) -> String {
+        let mut s = format!(
+            "use groth16_solana::groth16::Groth16Verifyingkey;\n\npub const VERIFYINGKEY: Groth16Verifyingkey =  Groth16Verifyingkey {{\n\tnr_pubinputs: {},\n\n",
+            self.nr_pubinputs
+        );
+        let rows = |s: &mut String, bytes: &[u8], indent: &str| {
+            for row in bytes.chunks(32) {
+                s.push_str(indent);
+                for byte in row {
+                    s.push_str(&format!("{},", byte));
+                }
+                s.push('\n');
+            }
+        };
+        for (name, bytes) in [
+            ("vk_alpha_g1", &self.vk_alpha_g1[..]),
+            ("vk_beta_g2", &self.vk_beta_g2[..]),
+            ("vk_gamme_g2", &self.vk_gamme_g2[..]),
+            ("vk_delta_g2", &self.vk_delta_g2[..]),
+        ] {
+            s.push_str(&format!("\t{}: [\n", name));
+            rows(&mut s, bytes, "\t\t");
+            s.push_str("\t],\n\n");
+        }
+        s.push_str("\tvk_ic: &[\n");
+        for ic in &self.vk_ic {
+            s.push_str("\t\t[\n");
+            rows(&mut s, ic, "\t\t\t");
+            s.push_str("\t\t],\n");
+        }
+        s.push_str("\t]\n};");
+        s
+    }
+
+    /// Parses a module produced by `to_rust_source`, used to detect drift
+    /// between committed keys and their `verification_key.json`.
+    pub fn from_rust_source(source: &str) -> Result<Self, ConvertError> {
+        let labels = [
+            "nr_pubinputs:",
+            "vk_alpha_g1:",
+            "vk_beta_g2:",
+            "vk_gamme_g2:",
+            "vk_delta_g2:",
+            "vk_ic:",
+        ];
+        let mut sections: Vec<Vec<u64>> = Vec::with_capacity(labels.len());
+        for (i, label) in labels.iter().enumerate() {
+            let start = source
+                .find(label)
+                .ok_or_else(|| ConvertError::InvalidSource(format!("missing {}", label)))?
+                + label.len();
+            let end = match labels.get(i + 1) {
+                Some(next) => source
+                    .find(next)
+                    .ok_or_else(|| ConvertError::InvalidSource(format!("missing {}", next)))?,
+                None => source.len(),
+            };
+            if end < start {
+                return Err(ConvertError::InvalidSource(format!(
+                    "{} out of order",
+                    label
+                )));
+            }
+            sections.push(numbers(&source[start..end])?);
+        }
+        let nr_pubinputs = match sections[0].as_slice() {
+            [n] => *n,
+            _ => return Err(ConvertError::InvalidSource("nr_pubinputs".to_string())),
+        };
+        let bytes = |section: &[u64]| -> Result<Vec<u8>, ConvertError> {
+            section
+                .iter()
+                .map(|n| {
+                    u8::try_from(*n)
+                        .map_err(|_| ConvertError::InvalidSource(format!("{} is not a byte", n)))
+                })
+                .collect()
+        };
+        let fixed = |section: &[u64], name: &str| -> Result<Vec<u8>, ConvertError> {
+            let output = bytes(section)?;
+            let expected = if name == "vk_alpha_g1" { 64 } else { 128 };
+            if output.len() != expected {
+                return Err(ConvertError::InvalidSource(format!(
+                    "{} has {} bytes, expected {}",
+                    name,
+                    output.len(),
+                    expected
+                )));
+            }
+            Ok(output)
+        };
+        let ic = bytes(&sections[5])?;
+        if ic.len() % 64 != 0 {
+            return Err(ConvertError::InvalidSource("vk_ic".to_string()));
+        }
+        Ok(Self {
+            nr_pubinputs,
+            vk_alpha_g1: fixed(&sections[1], "vk_alpha_g1")?.try_into().unwrap(),
+            vk_beta_g2: fixed(&sections[2], "vk_beta_g2")?.try_into().unwrap(),
+            vk_gamme_g2: fixed(&sections[3], "vk_gamme_g2")?.try_into().unwrap(),
+            vk_delta_g2: fixed(&sections[4], "vk_delta_g2")?.try_into().unwrap(),
+            vk_ic: ic.chunks(64).map(|c| c.try_into().unwrap()).collect(),
+        })
+    }
+
+    /// The fingerprint a pool pins in `MerkleState::verifying_key_hash`.
+    pub fn hash(&self) -> [u8; 32] {
+        groth16_key_hash(
+            &self.vk_alpha_g1,
+            &self.vk_beta_g2,
+            &self.vk_gamme_g2,
+            &self.vk_delta_g2,
+            &self.vk_ic,
+        )
+    }
+
+    pub fn to_account_data(&self) -> Vec<u8> {
+        self.try_to_vec().unwrap()
+    }
+
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ConvertError> {
+        Self::try_from_slice(data).map_err(|e| ConvertError::InvalidAccountData(e.to_string()))
+    }
+}
+
+fn numbers(section: &str) -> Result<Vec<u64>, ConvertError> {
+    section
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<u64>()
+                .map_err(|_| ConvertError::InvalidSource(s.to_string()))
+        })
+        .collect()
+}
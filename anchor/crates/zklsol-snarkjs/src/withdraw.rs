@@ -0,0 +1,110 @@
+//! snarkjs `proof.json` / `public.json` to `WithdrawArgs`.
+use crate::error::ConvertError;
+use crate::json::{
+    check_protocol, g1_from_json, g1_to_json, g2_from_json, g2_to_json, parse_field_element,
+    protocol_of, ProofJson,
+};
+use crate::layout::{proof_encoding, PROOF_SIZE};
+use crate::plonk::{plonk_withdraw_args, PLONK};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorSerialize;
+use zklsol::instructions::WithdrawArgs;
+use zklsol::withdraw_verifier::{negate_proof_a, WITHDRAW_PUBLIC_INPUTS};
+
+/// Same bytes `ZkHelper.convertProofToBytes` produces.
+pub fn proof_bytes(proof: &ProofJson) -> Result<[u8; PROOF_SIZE], ConvertError> {
+    check_protocol(&proof.protocol, &proof.curve)?;
+    let mut output = [0u8; PROOF_SIZE];
+    output[..64].copy_from_slice(&g1_from_json(&proof.pi_a)?);
+    output[64..192].copy_from_slice(&g2_from_json(&proof.pi_b)?);
+    output[192..].copy_from_slice(&g1_from_json(&proof.pi_c)?);
+    Ok(output)
+}
+
+pub fn proof_json(bytes: &[u8; PROOF_SIZE]) -> ProofJson {
+    ProofJson {
+        pi_a: g1_to_json(&bytes[..64]),
+        pi_b: g2_to_json(&bytes[64..192]),
+        pi_c: g1_to_json(&bytes[192..]),
+        protocol: "groth16".to_string(),
+        curve: "bn128".to_string(),
+    }
+}
+
+/// `(proof_a, proof_b, proof_c)` as `Groth16Verifier` takes them.
+pub type VerifierProof = ([u8; 64], [u8; 128], [u8; 64]);
+
+/// The triple handed to `Groth16Verifier`, with `proof_a` negated exactly as
+/// `withdraw` does it.
+pub fn verifier_proof(bytes: &[u8; PROOF_SIZE]) -> Result<VerifierProof, ConvertError> {
+    let proof_a =
+        negate_proof_a(bytes).map_err(|e| ConvertError::InvalidPoint(format!("proof_a: {}", e)))?;
+    Ok((
+        proof_a,
+        bytes[64..192].try_into().unwrap(),
+        bytes[192..].try_into().unwrap(),
+    ))
+}
+
+pub fn public_signals(public: &[String]) -> Result<Vec<[u8; 32]>, ConvertError> {
+    public.iter().map(|s| parse_field_element(s)).collect()
+}
+
+/// `root` isn't a public signal of the withdraw circuit, so it has to be provided.
+/// When `recipient` is given it must be the first public signal, unreduced,
+/// because that is the value `withdraw` feeds to the verifier.
+/// The proof is emitted compressed unless `compressed` is false.
+/// PLONK proofs are always sent as they are.
+pub fn withdraw_args(
+    proof: &ProofJson,
+    public: &[String],
+    root: [u8; 32],
+    recipient: Option<&Pubkey>,
+    compressed: bool,
+) -> Result<WithdrawArgs, ConvertError> {
+    Ok(WithdrawArgs {
+        nullifier_hash: withdraw_signals(public, recipient)?,
+        proof: proof_encoding(&proof_bytes(proof)?, compressed)?,
+        root,
+    })
+}
+
+/// Checks the withdraw circuit's public signals and returns the nullifier hash.
+pub fn withdraw_signals(
+    public: &[String],
+    recipient: Option<&Pubkey>,
+) -> Result<[u8; 32], ConvertError> {
+    let signals = public_signals(public)?;
+    if signals.len() != WITHDRAW_PUBLIC_INPUTS {
+        return Err(ConvertError::PublicSignalsLength {
+            expected: WITHDRAW_PUBLIC_INPUTS,
+            actual: signals.len(),
+        });
+    }
+    if let Some(recipient) = recipient {
+        if signals[0] != recipient.to_bytes() {
+            return Err(ConvertError::PublicSignalMismatch("recipient"));
+        }
+    }
+    Ok(signals[1])
+}
+
+pub fn withdraw_args_from_json(
+    proof: &str,
+    public: &str,
+    root: [u8; 32],
+    recipient: Option<&Pubkey>,
+    compressed: bool,
+) -> Result<WithdrawArgs, ConvertError> {
+    let public: Vec<String> = serde_json::from_str(public)?;
+    if protocol_of(proof)? == PLONK {
+        return plonk_withdraw_args(&serde_json::from_str(proof)?, &public, root, recipient);
+    }
+    let proof: ProofJson = serde_json::from_str(proof)?;
+    withdraw_args(&proof, &public, root, recipient, compressed)
+}
+
+/// Borsh encoding of the instruction argument, as it appears after the discriminator.
+pub fn withdraw_args_bytes(args: &WithdrawArgs) -> Vec<u8> {
+    args.try_to_vec().unwrap()
+}
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::Pubkey;
+use std::fs;
+use std::path::PathBuf;
+use zklsol::plonk_verifier::PLONK_PROOF_SIZE;
+use zklsol::withdraw_verifier::{circuit_name, ProofEncoding, VERIFYING_KEYS};
+use zklsol_snarkjs::json::be_to_decimal;
+use zklsol_snarkjs::layout::{compress_proof_bytes, decompress_proof_bytes};
+use zklsol_snarkjs::plonk::{plonk_proof_bytes, plonk_proof_json, plonk_withdraw_args};
+use zklsol_snarkjs::withdraw::{
+    proof_bytes, proof_json, verifier_proof, withdraw_args, withdraw_args_from_json,
+};
+use zklsol_snarkjs::{ConvertError, VerifyingKeyBytes};
+
+fn anchor_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn read(path: &str) -> String {
+    fs::read_to_string(anchor_dir().join(path)).unwrap()
+}
+
+fn verifying_key(name: &str) -> VerifyingKeyBytes {
+    VerifyingKeyBytes::from_json_str(&read(&format!(
+        "circuits-output/{}/verification_key.json",
+        name
+    )))
+    .unwrap()
+}
+
+#[test]
+fn verifying_key_source_matches_committed_test() {
+    for name in ["withdraw3", "withdraw20"] {
+        let key = verifying_key(name);
+        assert_eq!(key.nr_pubinputs, 3);
+        let source = key.to_rust_source();
+        for committed in [
+            format!("circuits-output/{}_rs/{}_verifying_key.rs", name, name),
+            format!("programs/zklsol/src/{}_verifying_key.rs", name),
+        ] {
+            let committed = read(&committed);
+            assert_eq!(source, committed, "{}", name);
+            assert_eq!(
+                VerifyingKeyBytes::from_rust_source(&committed).unwrap(),
+                key
+            );
+        }
+    }
+}
+
+#[test]
+fn verifying_key_hash_matches_program_keys_test() {
+    for name in ["withdraw3", "withdraw20"] {
+        let compiled = VERIFYING_KEYS
+            .iter()
+            .find(|entry| entry.circuit == name)
+            .unwrap();
+        assert_eq!(verifying_key(name).hash(), compiled.key.hash(), "{}", name);
+        assert_eq!(circuit_name(&compiled.circuit_id().unwrap()), Some(name));
+    }
+    assert_ne!(
+        verifying_key("withdraw3").hash(),
+        verifying_key("withdraw20").hash()
+    );
+}
+
+#[test]
+fn verifying_key_account_data_roundtrip_test() {
+    let key = verifying_key("withdraw3");
+    let data = key.to_account_data();
+    assert_eq!(VerifyingKeyBytes::from_account_data(&data).unwrap(), key);
+    assert!(VerifyingKeyBytes::from_account_data(&data[..data.len() - 1]).is_err());
+}
+
+#[test]
+fn verifying_key_rejects_bad_json_test() {
+    let mut json: serde_json::Value =
+        serde_json::from_str(&read("circuits-output/withdraw3/verification_key.json")).unwrap();
+    json["protocol"] = "plonk".into();
+    assert!(matches!(
+        VerifyingKeyBytes::from_json_str(&json.to_string()),
+        Err(ConvertError::UnsupportedProtocol(_))
+    ));
+    json["protocol"] = "groth16".into();
+    json["vk_alpha_1"][0] = "1".into();
+    assert!(matches!(
+        VerifyingKeyBytes::from_json_str(&json.to_string()),
+        Err(ConvertError::InvalidPoint(_))
+    ));
+}
+
+/// Curve points taken from the verifying key stand in for a proof.
+fn sample_proof_bytes() -> [u8; 256] {
+    let key = verifying_key("withdraw3");
+    let mut bytes = [0u8; 256];
+    bytes[..64].copy_from_slice(&key.vk_ic[1]);
+    bytes[64..192].copy_from_slice(&key.vk_delta_g2);
+    bytes[192..].copy_from_slice(&key.vk_ic[2]);
+    bytes
+}
+
+#[test]
+fn proof_json_roundtrip_test() {
+    let bytes = sample_proof_bytes();
+    let json = proof_json(&bytes);
+    assert_eq!(proof_bytes(&json).unwrap(), bytes);
+    let (proof_a, proof_b, proof_c) = verifier_proof(&bytes).unwrap();
+    assert_ne!(proof_a[..], bytes[..64]);
+    assert_eq!(proof_a[..32], bytes[..32]);
+    assert_eq!(proof_b[..], bytes[64..192]);
+    assert_eq!(proof_c[..], bytes[192..]);
+}
+
+#[test]
+fn withdraw_args_test() {
+    let bytes = sample_proof_bytes();
+    let json = proof_json(&bytes);
+    let recipient = Pubkey::new_from_array([7u8; 32]);
+    let nullifier_hash = [3u8; 32];
+    let public = vec![
+        be_to_decimal(&recipient.to_bytes()),
+        be_to_decimal(&nullifier_hash),
+    ];
+    let args = withdraw_args(&json, &public, [4u8; 32], Some(&recipient), false).unwrap();
+    assert_eq!(args.proof, ProofEncoding::Uncompressed(bytes));
+    let compressed = withdraw_args(&json, &public, [4u8; 32], Some(&recipient), true).unwrap();
+    let compressed_bytes = compress_proof_bytes(&bytes).unwrap();
+    assert_eq!(
+        compressed.proof,
+        ProofEncoding::Compressed(compressed_bytes)
+    );
+    assert_eq!(decompress_proof_bytes(&compressed_bytes).unwrap(), bytes);
+    assert_eq!(args.nullifier_hash, nullifier_hash);
+    assert_eq!(args.root, [4u8; 32]);
+
+    let other = Pubkey::new_from_array([8u8; 32]);
+    assert!(matches!(
+        withdraw_args(&json, &public, [4u8; 32], Some(&other), true),
+        Err(ConvertError::PublicSignalMismatch("recipient"))
+    ));
+    assert!(matches!(
+        withdraw_args(&json, &public[..1], [4u8; 32], None, true),
+        Err(ConvertError::PublicSignalsLength { .. })
+    ));
+}
+
+#[test]
+fn plonk_withdraw_args_test() {
+    let key = verifying_key("withdraw3");
+    let mut bytes = [0u8; PLONK_PROOF_SIZE];
+    for (i, chunk) in bytes[..9 * 64].chunks_mut(64).enumerate() {
+        chunk.copy_from_slice(&key.vk_ic[i % key.vk_ic.len()]);
+    }
+    for (i, chunk) in bytes[9 * 64..].chunks_mut(32).enumerate() {
+        chunk[31] = i as u8 + 1;
+    }
+    let json = plonk_proof_json(&bytes);
+    assert_eq!(plonk_proof_bytes(&json).unwrap(), bytes);
+
+    let recipient = Pubkey::new_from_array([7u8; 32]);
+    let public = vec![
+        be_to_decimal(&recipient.to_bytes()),
+        be_to_decimal(&[3u8; 32]),
+    ];
+    let args = plonk_withdraw_args(&json, &public, [4u8; 32], Some(&recipient)).unwrap();
+    assert_eq!(args.proof, ProofEncoding::Plonk(Box::new(bytes)));
+    assert_eq!(args.nullifier_hash, [3u8; 32]);
+
+    let from_str = withdraw_args_from_json(
+        &serde_json::to_string(&json).unwrap(),
+        &serde_json::to_string(&public).unwrap(),
+        [4u8; 32],
+        Some(&recipient),
+        true,
+    )
+    .unwrap();
+    assert_eq!(from_str.proof, args.proof);
+
+    let mut groth16 = json.clone();
+    groth16.protocol = "groth16".to_string();
+    assert!(matches!(
+        plonk_proof_bytes(&groth16),
+        Err(ConvertError::UnsupportedProtocol(_))
+    ));
+}
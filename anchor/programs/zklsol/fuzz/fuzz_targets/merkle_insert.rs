@@ -0,0 +1,54 @@
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use zklsol::state::merkle_node::MerkleNode;
+use zklsol::state::merkle_proof::MerkleProof;
+use zklsol::state::merkle_tree::MerkleTree;
+use zklsol::state::merkle_zeros::MerkleZeros;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    depth: u8,
+    leaves: Vec<[u8; 32]>,
+}
+
+fuzz_target!(|input: Input| {
+    let depth = input.depth % 8 + 1;
+    let zeros = MerkleZeros::new(depth, 0);
+    let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+    for leaf in input.leaves.iter().take(64) {
+        let full = tree.next_index > tree.number_of_leaves;
+        let proof = match tree.insert(leaf, &zeros) {
+            Ok(proof) => proof,
+            Err(_) => {
+                assert!(full);
+                return;
+            }
+        };
+        // One changed node per level, the leaf first and the new root last.
+        let leaf_index = tree.next_index - 1;
+        let height = tree.height().unwrap();
+        assert_eq!(proof.nodes.len(), depth as usize);
+        assert_eq!(proof.nodes[0].node, MerkleNode::deserialize(leaf));
+        let path = tree.generate_proof_path(leaf_index).unwrap();
+        for (level, changed) in proof.nodes.iter().enumerate() {
+            assert_eq!(changed.level as usize, level);
+            let index = MerkleProof::node_index(leaf_index, changed.level, height).unwrap();
+            let expected = match path[level].as_slice() {
+                [left, right, is_right] => {
+                    if *is_right == 1 {
+                        *right
+                    } else {
+                        *left
+                    }
+                }
+                [root] => *root,
+                _ => unreachable!(),
+            };
+            assert_eq!(index, expected);
+        }
+        let root = proof.root().unwrap();
+        assert!(tree.known_root(&root.hash));
+    }
+});
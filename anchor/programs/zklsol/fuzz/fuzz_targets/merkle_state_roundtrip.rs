@@ -0,0 +1,76 @@
+#![no_main]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator};
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use zklsol::state::merkle_state::MerkleState;
+use zklsol::withdraw_verifier::ProofSystem;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    bump: u8,
+    depth: u64,
+    deposit_size: u64,
+    number_of_deposits: u64,
+    current_root_index: u8,
+    next_index: u64,
+    lowest_pending_proof_index: u64,
+    highest_pending_proof_index: u64,
+    mint: [u8; 32],
+    roots: Vec<[u8; 32]>,
+    filled_sub_trees: Vec<[u8; 32]>,
+    plonk: bool,
+    verifying_key_hash: [u8; 32],
+    circuit_id: [u8; 32],
+    deposit_proof_required: bool,
+    deposit_verifying_key_hash: [u8; 32],
+    raw: Vec<u8>,
+}
+
+fn serialize(state: &MerkleState) -> Vec<u8> {
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    data
+}
+
+fuzz_target!(|input: Input| {
+    let mut state = MerkleState {
+        bump: input.bump,
+        depth: input.depth,
+        deposit_size: input.deposit_size,
+        number_of_deposits: input.number_of_deposits,
+        current_root_index: input.current_root_index,
+        next_index: input.next_index,
+        lowest_pending_proof_index: input.lowest_pending_proof_index,
+        highest_pending_proof_index: input.highest_pending_proof_index,
+        mint: Pubkey::new_from_array(input.mint),
+        roots: input.roots,
+        filled_sub_trees: input.filled_sub_trees,
+        verifying_key_hash: input.verifying_key_hash,
+        circuit_id: input.circuit_id,
+        deposit_proof_required: input.deposit_proof_required,
+        deposit_verifying_key_hash: input.deposit_verifying_key_hash,
+        ..Default::default()
+    };
+    if input.plonk {
+        state.proof_system = ProofSystem::Plonk;
+    }
+    let data = serialize(&state);
+    if state.roots.len() <= 32 && state.filled_sub_trees.len() <= 32 {
+        assert!(data.len() <= MerkleState::SIZE);
+    }
+    let decoded = MerkleState::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(decoded, state);
+    // A corrupt account must fail cleanly, never panic.
+    let _ = decoded.to_merkle_tree();
+
+    // Arbitrary account data behind a valid discriminator.
+    let mut raw = MerkleState::discriminator().to_vec();
+    raw.extend_from_slice(&input.raw);
+    if let Ok(decoded) = MerkleState::try_deserialize(&mut raw.as_slice()) {
+        let reserialized = serialize(&decoded);
+        assert_eq!(reserialized.as_slice(), &raw[..reserialized.len()]);
+        let _ = decoded.to_merkle_tree();
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigUint;
+use zklsol::state::merkle_node::MerkleNode;
+
+const BN254_MODULUS: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+fuzz_target!(|input: &[u8]| {
+    let p = BigUint::parse_bytes(BN254_MODULUS.as_bytes(), 10).unwrap();
+    let mut data = [0u8; 32];
+    let len = input.len().min(32);
+    data[..len].copy_from_slice(&input[..len]);
+    let expected = (BigUint::from_bytes_be(&data) % &p).to_bytes_be();
+    let mut expected_bytes = [0u8; 32];
+    expected_bytes[32 - expected.len()..].copy_from_slice(&expected);
+
+    let output = MerkleNode::mod_input(input);
+    assert_eq!(output, expected_bytes);
+    assert_eq!(MerkleNode::mod_input(&output), output);
+    assert_eq!(MerkleNode::hash(input), MerkleNode::hash(&output));
+});
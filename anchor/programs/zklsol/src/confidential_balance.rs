@@ -0,0 +1,157 @@
+//! Token-2022 confidential transfer plumbing for pools whose mint has the extension.
+//!
+//! A pool pays out of its own confidential balance with a confidential `Transfer`, so the
+//! recipient doesn't sign the withdrawal and the payout never shows up in its public
+//! balance. The pool's ElGamal secret key is the scalar one: anyone can build the proof
+//! that spends from a pool, and the balance it hides is public anyway, it's the pool's
+//! deposits.
+use crate::error::ErrorCodes;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::confidential_transfer::instruction::{
+    deposit, inner_apply_pending_balance, inner_transfer, ConfidentialTransferInstruction,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::confidential_transfer::{
+    ConfidentialTransferAccount, ConfidentialTransferMint,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::proof::ProofLocation;
+use anchor_spl::token_2022::spl_token_2022::state::{Account, Mint};
+use solana_zk_token_sdk::zk_token_elgamal::pod::AeCiphertext;
+
+/// `TokenInstruction::ConfidentialTransferExtension`.
+pub const CONFIDENTIAL_TRANSFER_EXTENSION: u8 = 27;
+
+/// Size of the AES ciphertext of an available balance, see `DecryptableBalance`.
+pub const DECRYPTABLE_BALANCE_SIZE: usize = 36;
+
+/// ElGamal public key of the secret key one, the compressed Pedersen base `H`.
+pub const POOL_ELGAMAL_PUBKEY: [u8; 32] = [
+    140, 146, 64, 180, 86, 169, 230, 220, 101, 195, 119, 161, 4, 141, 116, 95, 148, 160, 140, 219,
+    127, 68, 203, 205, 123, 70, 243, 64, 72, 135, 17, 52,
+];
+
+/// Seed of the AES key a pool's decryptable balance is encrypted with,
+/// `AeKey::from_seed(POOL_AE_KEY_SEED)`. Public like the ElGamal secret.
+pub const POOL_AE_KEY_SEED: &[u8] = b"zklsol confidential pool balance";
+
+/// Every payout applies the pool's pending balance, so it never holds more than the
+/// payout's own deposit and whatever was credited to it directly since the last one.
+pub const POOL_MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER: u64 = 65536;
+
+/// Token-2022 pools can only pay out through `withdraw_confidential`, so their mint needs
+/// the confidential transfer extension, approving new accounts so the pool's own can send.
+/// A transfer fee would leave the pool holding less than it owes. SPL Token mints pass.
+pub fn check_pool_mint(owner: &Pubkey, data: &[u8]) -> std::result::Result<(), ErrorCodes> {
+    if *owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let mint =
+        StateWithExtensions::<Mint>::unpack(data).map_err(|_| ErrorCodes::UnsupportedMint)?;
+    let confidential = mint
+        .get_extension::<ConfidentialTransferMint>()
+        .map_err(|_| ErrorCodes::UnsupportedMint)?;
+    if !bool::from(confidential.auto_approve_new_accounts)
+        || mint.get_extension::<TransferFeeConfig>().is_ok()
+    {
+        return Err(ErrorCodes::UnsupportedMint);
+    }
+    Ok(())
+}
+
+/// The confidential transfer state of a pool's token account, an error until
+/// `configure_confidential_pool` has run.
+pub fn pool_confidential_account(
+    data: &[u8],
+) -> std::result::Result<ConfidentialTransferAccount, ErrorCodes> {
+    StateWithExtensions::<Account>::unpack(data)
+        .and_then(|account| {
+            account
+                .get_extension::<ConfidentialTransferAccount>()
+                .copied()
+        })
+        .map_err(|_| ErrorCodes::ConfidentialPoolNotConfigured)
+}
+
+/// `ConfigureAccount` for a pool's token account, with the pubkey validity proof of
+/// `POOL_ELGAMAL_PUBKEY` in `proof_context`. Built by hand as the spl-token-2022 builder
+/// is client-only.
+pub fn configure_pool_instruction(
+    token_account: &Pubkey,
+    mint: &Pubkey,
+    proof_context: &Pubkey,
+    merkle: &Pubkey,
+    decryptable_zero_balance: &[u8; DECRYPTABLE_BALANCE_SIZE],
+) -> Instruction {
+    let mut data = vec![
+        CONFIDENTIAL_TRANSFER_EXTENSION,
+        ConfidentialTransferInstruction::ConfigureAccount.into(),
+    ];
+    data.extend_from_slice(decryptable_zero_balance);
+    data.extend_from_slice(&POOL_MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER.to_le_bytes());
+    // A zero offset reads the proof from the context state account.
+    data.push(0);
+    Instruction {
+        program_id: spl_token_2022::ID,
+        accounts: vec![
+            AccountMeta::new(*token_account, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*proof_context, false),
+            AccountMeta::new_readonly(*merkle, true),
+        ],
+        data,
+    }
+}
+
+/// `Deposit` of `amount` from the pool's public balance, `ApplyPendingBalance`, then a
+/// confidential `Transfer` of it to the recipient, all authorized by `merkle`.
+/// `pool` is the pool account's state before the deposit. The transfer proof in
+/// `proof_context` is made against the available balance after the apply, and
+/// `new_decryptable_available_balance` is what remains after the transfer.
+#[allow(clippy::too_many_arguments)]
+pub fn pool_payout_instructions(
+    token_account: &Pubkey,
+    mint: &Pubkey,
+    recipient_token_account: &Pubkey,
+    proof_context: &Pubkey,
+    merkle: &Pubkey,
+    pool: &ConfidentialTransferAccount,
+    amount: u64,
+    decimals: u8,
+    new_decryptable_available_balance: &[u8; DECRYPTABLE_BALANCE_SIZE],
+) -> std::result::Result<[Instruction; 3], ProgramError> {
+    Ok([
+        deposit(
+            &spl_token_2022::ID,
+            token_account,
+            mint,
+            amount,
+            decimals,
+            merkle,
+            &[],
+        )?,
+        // The transfer overwrites the decryptable balance, so the apply keeps the old one.
+        inner_apply_pending_balance(
+            &spl_token_2022::ID,
+            token_account,
+            u64::from(pool.pending_balance_credit_counter) + 1,
+            pool.decryptable_available_balance,
+            merkle,
+            &[],
+        )?,
+        inner_transfer(
+            &spl_token_2022::ID,
+            token_account,
+            mint,
+            recipient_token_account,
+            AeCiphertext(*new_decryptable_available_balance),
+            merkle,
+            &[],
+            ProofLocation::ContextStateAccount(proof_context),
+        )?,
+    ])
+}
@@ -67,4 +67,46 @@ pub enum ErrorCodes {
     SignerCantMatchRecipient,
     #[msg("Value can't be Poseidon hashed")]
     ValueCantBePoseidonHashed,
+    #[msg("Invalid Merkle node index")]
+    InvalidNodeIndex,
+    #[msg("Nullifier already spent")]
+    NullifierAlreadySpent,
+    #[msg("Invalid compressed proof")]
+    InvalidCompressedProof,
+    #[msg("Proof doesn't match the pool's proof system")]
+    UnsupportedProofSystem,
+    #[msg("PLONK scalar is not a field element")]
+    PlonkInvalidScalar,
+    #[msg("PLONK public inputs don't match the verifying key")]
+    PlonkInvalidPublicInputs,
+    #[msg("PLONK curve operation failed")]
+    PlonkCurveError,
+    #[msg("PLONK verification failed")]
+    PlonkVerifyError,
+    #[msg("Batch size is out of range")]
+    InvalidBatchSize,
+    #[msg("Wrong number of remaining accounts")]
+    InvalidRemainingAccounts,
+    #[msg("Verifying key doesn't match the one pinned by the pool")]
+    VerifyingKeyMismatch,
+    #[msg("Note memo is too large")]
+    NoteMemoTooLarge,
+    #[msg("Pool requires a proof that the deposited commitment is a note")]
+    DepositProofRequired,
+    #[msg("Commitment was already deposited into this pool")]
+    DuplicateCommitment,
+    #[msg("Circuit name is longer than 32 bytes")]
+    CircuitNameTooLong,
+    #[msg("Token-2022 mint needs auto-approved confidential transfers and no transfer fee")]
+    UnsupportedMint,
+    #[msg("Pool's token account isn't configured for confidential transfers")]
+    ConfidentialPoolNotConfigured,
+    #[msg("Pool's ElGamal key isn't the public pool key")]
+    PoolElGamalKeyMismatch,
+    #[msg("No verifying key for this depth and proof system")]
+    VerifyingKeyNotFound,
+    #[msg("Account isn't a pending proof in the old layout")]
+    NotLegacyPendingProof,
+    #[msg("Pending proof is in the old layout, upgrade it first")]
+    LegacyPendingProof,
 }
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+/// Upper bound on `DepositArgs::memo`, room for an X25519 + ChaCha20-Poly1305 note
+/// with space to spare for other formats.
+pub const MAX_NOTE_MEMO_SIZE: usize = 256;
+
+/// Emitted by `deposit` for every leaf, so a wallet holding a viewing key can find its
+/// notes by scanning the pool's transactions.
+#[event]
+pub struct DepositEvent {
+    pub merkle: Pubkey,
+    pub index: u64,
+    pub commitment: [u8; 32],
+    /// The note encrypted to the depositor's viewing key, opaque to the program.
+    pub memo: Option<Vec<u8>>,
+}
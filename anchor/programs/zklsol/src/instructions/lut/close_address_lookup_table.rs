@@ -27,7 +27,7 @@ pub struct CloseAddressLookupTable<'info> {
     mut,
     has_one = address_lookup_table,
     has_one = signer,
-    seeds = [UserAddressLookupTable::SEED.as_bytes().as_ref(), signer.key().as_ref()],
+    seeds = [UserAddressLookupTable::SEED.as_bytes(), signer.key().as_ref()],
     bump = user_address_lookup_table.bump
     )]
     pub user_address_lookup_table: Box<Account<'info, UserAddressLookupTable>>,
@@ -31,7 +31,7 @@ pub struct CreateAddressLookupTable<'info> {
     #[account(
     init,
     payer = signer,
-    seeds = [UserAddressLookupTable::SEED.as_bytes().as_ref(), signer.key().as_ref()],
+    seeds = [UserAddressLookupTable::SEED.as_bytes(), signer.key().as_ref()],
     space = UserAddressLookupTable::SIZE,
     bump
     )]
@@ -27,7 +27,7 @@ pub struct ExtendAddressLookupTable<'info> {
     mut,
     has_one = address_lookup_table,
     has_one = signer,
-    seeds = [UserAddressLookupTable::SEED.as_bytes().as_ref(), signer.key().as_ref()],
+    seeds = [UserAddressLookupTable::SEED.as_bytes(), signer.key().as_ref()],
     bump = user_address_lookup_table.bump
     )]
     pub user_address_lookup_table: Box<Account<'info, UserAddressLookupTable>>,
@@ -39,11 +39,7 @@ pub fn extend_address_lookup_table(ctx: Context<ExtendAddressLookupTable>) -> Re
     let user_address_lookup_table = &mut ctx.accounts.user_address_lookup_table;
     let address_lookup_table = &ctx.accounts.address_lookup_table;
     let system_program = &ctx.accounts.system_program;
-    let new_addresses = ctx
-        .remaining_accounts
-        .iter()
-        .map(|i| i.key.clone())
-        .collect();
+    let new_addresses = ctx.remaining_accounts.iter().map(|i| *i.key).collect();
     let ix = extend_lookup_table(
         address_lookup_table.key(),
         user_address_lookup_table.key(),
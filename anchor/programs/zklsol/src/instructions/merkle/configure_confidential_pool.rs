@@ -0,0 +1,109 @@
+use crate::confidential_balance::{
+    configure_pool_instruction, pool_confidential_account, DECRYPTABLE_BALANCE_SIZE,
+    POOL_ELGAMAL_PUBKEY,
+};
+use crate::error::ErrorCodes;
+use crate::state::merkle_state::MerkleState;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ConfigureConfidentialPoolArgs {
+    /// Zero encrypted with the key of `POOL_AE_KEY_SEED`.
+    pub decryptable_zero_balance: [u8; DECRYPTABLE_BALANCE_SIZE],
+}
+
+#[derive(Accounts)]
+pub struct ConfigureConfidentialPoolContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+    has_one = mint,
+    seeds = [MerkleState::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    bump = merkle.bump
+    )]
+    pub merkle: Box<Account<'info, MerkleState>>,
+    #[account(
+    mut,
+    token::mint = mint,
+    token::authority = merkle,
+    seeds = [MerkleState::TOKEN_SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    bump
+    )]
+    pub merkle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Token-2022 checks it holds a pubkey validity proof
+    pub proof_context: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Gives a Token-2022 pool's token account its confidential balance, under
+/// `POOL_ELGAMAL_PUBKEY` so anyone can prove payouts from it. Anyone can call it once
+/// the pool exists; `withdraw_confidential` fails until someone has.
+pub fn configure_confidential_pool(
+    ctx: Context<ConfigureConfidentialPoolContext>,
+    args: ConfigureConfidentialPoolArgs,
+) -> Result<()> {
+    let signer = &ctx.accounts.signer;
+    let merkle = &ctx.accounts.merkle;
+    let merkle_token_account = &ctx.accounts.merkle_token_account;
+    let proof_context = &ctx.accounts.proof_context;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let mint = &ctx.accounts.mint;
+
+    let mint_key = mint.key();
+    let depth_binding = merkle.depth.to_le_bytes();
+    let seeds = [
+        MerkleState::SEED.as_bytes(),
+        mint_key.as_ref(),
+        depth_binding.as_ref(),
+        &[merkle.bump],
+    ];
+    solana_program::program::invoke_signed(
+        &spl_token_2022::instruction::reallocate(
+            &spl_token_2022::ID,
+            &merkle_token_account.key(),
+            signer.key,
+            &merkle.key(),
+            &[],
+            &[ExtensionType::ConfidentialTransferAccount],
+        )?,
+        &[
+            merkle_token_account.to_account_info(),
+            signer.to_account_info(),
+            system_program.to_account_info(),
+            merkle.to_account_info(),
+            token_program.to_account_info(),
+        ],
+        &[seeds.as_slice()],
+    )?;
+    solana_program::program::invoke_signed(
+        &configure_pool_instruction(
+            &merkle_token_account.key(),
+            &mint_key,
+            proof_context.key,
+            &merkle.key(),
+            &args.decryptable_zero_balance,
+        ),
+        &[
+            merkle_token_account.to_account_info(),
+            mint.to_account_info(),
+            proof_context.to_account_info(),
+            merkle.to_account_info(),
+            token_program.to_account_info(),
+        ],
+        &[seeds.as_slice()],
+    )?;
+    let pool = pool_confidential_account(&merkle_token_account.to_account_info().data.borrow())?;
+    require!(
+        pool.elgamal_pubkey.0 == POOL_ELGAMAL_PUBKEY,
+        ErrorCodes::PoolElGamalKeyMismatch
+    );
+    Ok(())
+}
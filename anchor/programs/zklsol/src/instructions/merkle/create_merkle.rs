@@ -1,14 +1,21 @@
+use crate::confidential_balance::check_pool_mint;
+use crate::error::ErrorCodes;
 use crate::state::merkle_state::MerkleState;
 use crate::state::merkle_tree::MerkleTree;
 use crate::state::merkle_zeros::MerkleZeros;
+use crate::withdraw_verifier::ProofSystem;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CreateMerkleArgs {
     pub depth: u64,
     pub deposit_size: u64,
+    pub proof_system: ProofSystem,
+    /// Refuses deposits without a `deposit_commitment` proof.
+    /// Such pools can't be created while `DEPOSIT_VERIFYING_KEYS` is empty.
+    pub require_deposit_proof: bool,
 }
 
 #[derive(Accounts)]
@@ -18,7 +25,7 @@ pub struct CreateMerkle<'info> {
     pub signer: Signer<'info>,
     #[account(init,
     payer = signer,
-    seeds = [MerkleState::SEED.as_bytes().as_ref(), mint.key().as_ref(), args.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleState::SEED.as_bytes(), mint.key().as_ref(), args.depth.to_le_bytes().as_ref()],
     space = MerkleState::SIZE,
     bump
     )]
@@ -28,20 +35,22 @@ pub struct CreateMerkle<'info> {
     payer = signer,
     token::mint = mint,
     token::authority = merkle,
-    seeds = [MerkleState::TOKEN_SEED.as_bytes().as_ref(), mint.key().as_ref(), args.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleState::TOKEN_SEED.as_bytes(), mint.key().as_ref(), args.depth.to_le_bytes().as_ref()],
     bump
     )]
-    pub merkle_token_account: Box<Account<'info, TokenAccount>>,
+    pub merkle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(init,
     payer = signer,
-    seeds = [MerkleZeros::SEED.as_bytes().as_ref(), mint.key().as_ref(), args.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleZeros::SEED.as_bytes(), mint.key().as_ref(), args.depth.to_le_bytes().as_ref()],
     space = MerkleZeros::SIZE,
     bump
     )]
     pub merkle_zeros: Box<Account<'info, MerkleZeros>>,
-    pub mint: Box<Account<'info, Mint>>,
+    /// SPL Token or Token-2022, see `check_pool_mint`. Token-2022 pools pay out through
+    /// `withdraw_confidential` once `configure_confidential_pool` has run.
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -50,6 +59,8 @@ pub fn create_merkle(ctx: Context<CreateMerkle>, args: CreateMerkleArgs) -> Resu
     let mint = &ctx.accounts.mint;
     let merkle = &mut ctx.accounts.merkle;
     let merkle_zeros = &mut ctx.accounts.merkle_zeros;
+    let mint_info = mint.to_account_info();
+    check_pool_mint(mint_info.owner, &mint_info.data.borrow())?;
     let zeros = MerkleZeros::new(args.depth as u8, ctx.bumps.merkle_zeros);
     let m = MerkleTree::new(args.depth as u8, &zeros)?;
     merkle_zeros.bump = ctx.bumps.merkle_zeros;
@@ -57,9 +68,12 @@ pub fn create_merkle(ctx: Context<CreateMerkle>, args: CreateMerkleArgs) -> Resu
     merkle.bump = ctx.bumps.merkle;
     merkle.mint = mint.key();
     merkle.deposit_size = args.deposit_size;
+    merkle.proof_system = args.proof_system;
+    merkle.deposit_proof_required = args.require_deposit_proof;
     merkle.number_of_deposits = 0;
     merkle.lowest_pending_proof_index = 0;
     merkle.highest_pending_proof_index = 0;
     merkle.sync(m);
+    merkle.pin_verifying_key()?;
     Ok(())
 }
@@ -17,7 +17,7 @@ pub struct CreateMerkleNode<'info> {
     pub signer: Signer<'info>,
     #[account(init,
     payer = signer,
-    seeds = [MerkleNodeState::SEED.as_bytes().as_ref(), args.depth.to_le_bytes().as_ref(), args.index.to_le_bytes().as_ref()],
+    seeds = [MerkleNodeState::SEED.as_bytes(), args.depth.to_le_bytes().as_ref(), args.index.to_le_bytes().as_ref()],
     space = MerkleNodeState::SIZE,
     bump
     )]
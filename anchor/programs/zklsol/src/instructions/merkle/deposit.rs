@@ -1,17 +1,20 @@
 use crate::error::ErrorCodes;
-use crate::state::merkle_proof::PathElement;
-use crate::state::merkle_state::{MerkleNodeState, MerklePendingProofState, MerkleState};
+use crate::events::{DepositEvent, MAX_NOTE_MEMO_SIZE};
+use crate::state::merkle_state::{
+    DepositedCommitment, MerkleNodeState, MerklePendingProofState, MerkleState,
+};
 use crate::state::merkle_zeros::MerkleZeros;
 use crate::utils::{
-    clone_account_info, create_pda_account, is_native, transfer_sol, transfer_token,
+    clone_account_info, create_pda_account, is_native, transfer_sol, transfer_token_checked,
     unsafe_clone_account_info,
 };
+use crate::withdraw_verifier::{deposit_public_inputs, verify_withdraw, ProofEncoding};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_lang::solana_program::log::{sol_log, sol_log_compute_units};
 use anchor_lang::solana_program::poseidon::{hashv, Endianness, Parameters};
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -19,6 +22,12 @@ use std::rc::Rc;
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct DepositArgs {
     pub input: [u8; 32],
+    /// Encrypted note, see `DepositEvent::memo`.
+    pub memo: Option<Vec<u8>>,
+    /// `deposit_commitment` proof that `input` is Poseidon(nullifier, secret). Checked
+    /// whenever given, required by pools with `deposit_proof_required`. Refused with
+    /// `VerifyingKeyNotFound` while `DEPOSIT_VERIFYING_KEYS` is empty.
+    pub proof: Option<ProofEncoding>,
 }
 
 #[derive(Accounts)]
@@ -29,12 +38,13 @@ pub struct DepositContext<'info> {
     #[account(
     mut,
     associated_token::mint = mint,
-    associated_token::authority = signer
+    associated_token::authority = signer,
+    associated_token::token_program = token_program
     )]
-    pub signer_token_account: Box<Account<'info, TokenAccount>>,
+    pub signer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut,
     has_one = mint,
-    seeds = [MerkleState::SEED.as_bytes().as_ref(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleState::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
     bump = merkle.bump
     )]
     pub merkle: Box<Account<'info, MerkleState>>,
@@ -42,24 +52,31 @@ pub struct DepositContext<'info> {
     mut,
     token::mint = mint,
     token::authority = merkle,
-    seeds = [MerkleState::TOKEN_SEED.as_bytes().as_ref(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleState::TOKEN_SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
     bump
     )]
-    pub merkle_token_account: Box<Account<'info, TokenAccount>>,
+    pub merkle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-    seeds = [MerkleZeros::SEED.as_bytes().as_ref(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleZeros::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
     bump = merkle_zeros.bump
     )]
     pub merkle_zeros: Box<Account<'info, MerkleZeros>>,
     #[account(init,
-    seeds = [MerklePendingProofState::SEED.as_bytes().as_ref(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref(), merkle.next_index.to_le_bytes().as_ref()],
+    seeds = [MerklePendingProofState::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref(), merkle.next_index.to_le_bytes().as_ref()],
     payer = signer,
-    space = MerklePendingProofState::SIZE,
+    space = MerklePendingProofState::size(merkle.depth),
     bump
     )]
     pub pending_proof: Box<Account<'info, MerklePendingProofState>>,
-    pub token_program: Program<'info, Token>,
-    pub mint: Box<Account<'info, Mint>>,
+    #[account(init_if_needed,
+    payer = signer,
+    space = DepositedCommitment::SIZE,
+    seeds = [DepositedCommitment::SEED.as_bytes(), merkle.key().as_ref(), args.input.as_slice()],
+    bump
+    )]
+    pub deposited_commitment: Box<Account<'info, DepositedCommitment>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -75,19 +92,48 @@ pub fn deposit(ctx: Context<DepositContext>, args: DepositArgs) -> Result<()> {
     let merkle = &mut ctx.accounts.merkle;
     let merkle_zeros = &mut ctx.accounts.merkle_zeros;
     let pending_proof = &mut ctx.accounts.pending_proof;
+    let deposited_commitment = &mut ctx.accounts.deposited_commitment;
+    deposited_commitment.record(
+        ctx.bumps.deposited_commitment,
+        merkle.key(),
+        merkle.next_index,
+    )?;
     let hash = hashv(Parameters::Bn254X5, Endianness::BigEndian, &[&args.input]);
     require!(hash.is_ok(), ErrorCodes::ValueCantBePoseidonHashed);
+    if let Some(memo) = &args.memo {
+        require!(
+            memo.len() <= MAX_NOTE_MEMO_SIZE,
+            ErrorCodes::NoteMemoTooLarge
+        );
+    }
+    match &args.proof {
+        Some(proof) => {
+            let public_inputs = deposit_public_inputs(&args.input)?;
+            let verifying_key = merkle.deposit_verifying_key()?;
+            verify_withdraw(proof, &public_inputs, &verifying_key.key)?;
+        }
+        None => require!(
+            !merkle.deposit_proof_required,
+            ErrorCodes::DepositProofRequired
+        ),
+    }
     pending_proof.bump = ctx.bumps.pending_proof;
     pending_proof.index = merkle.next_index;
     msg!("pending_proof.index = {}", merkle.next_index);
     pending_proof.depth = merkle.depth;
-    let mut m = merkle.to_merkle_tree();
-    let proof = m.insert(&args.input, &merkle_zeros)?;
+    let mut m = merkle.to_merkle_tree()?;
+    let proof = m.insert(&args.input, merkle_zeros)?;
     pending_proof.proof = proof;
     pending_proof.mint = mint.key();
     merkle.number_of_deposits += 1;
     merkle.highest_pending_proof_index += 1;
     merkle.sync(m);
+    emit!(DepositEvent {
+        merkle: merkle.key(),
+        index: pending_proof.index,
+        commitment: args.input,
+        memo: args.memo,
+    });
     if is_native(&mint.to_account_info()) {
         transfer_sol(
             signer.to_account_info(),
@@ -96,12 +142,15 @@ pub fn deposit(ctx: Context<DepositContext>, args: DepositArgs) -> Result<()> {
             merkle.deposit_size,
         )?;
     } else {
-        transfer_token(
+        transfer_token_checked(
             signer_token_account.to_account_info(),
+            mint.to_account_info(),
             merkle_token_account.to_account_info(),
             token_program.to_account_info(),
             signer.to_account_info(),
             merkle.deposit_size,
+            mint.decimals,
+            &[],
         )?;
     }
     Ok(())
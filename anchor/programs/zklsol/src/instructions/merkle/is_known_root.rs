@@ -0,0 +1,19 @@
+use crate::state::merkle_state::MerkleState;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct IsKnownRootArgs {
+    pub root: [u8; 32],
+}
+
+#[derive(Accounts)]
+pub struct IsKnownRootContext<'info> {
+    pub merkle: Box<Account<'info, MerkleState>>,
+}
+
+/// Whether `withdraw` would accept `root` for this pool right now. Read-only, meant
+/// to be simulated, the answer comes back as return data.
+pub fn is_known_root(ctx: Context<IsKnownRootContext>, args: IsKnownRootArgs) -> Result<bool> {
+    let m = ctx.accounts.merkle.to_merkle_tree()?;
+    Ok(m.known_root(&args.root))
+}
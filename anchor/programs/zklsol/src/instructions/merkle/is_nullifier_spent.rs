@@ -0,0 +1,31 @@
+use crate::state::merkle_state::{MerkleState, NullifierHash};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct IsNullifierSpentArgs {
+    pub nullifier_hash: [u8; 32],
+}
+
+#[derive(Accounts)]
+#[instruction(args: IsNullifierSpentArgs)]
+pub struct IsNullifierSpentContext<'info> {
+    pub merkle: Box<Account<'info, MerkleState>>,
+    /// Check only its existence is read, it may not have been created yet
+    #[account(
+    seeds = [NullifierHash::SEED.as_bytes(), merkle.depth.to_le_bytes().as_ref(), args.nullifier_hash.as_slice()],
+    bump
+    )]
+    pub nullifier_hash: UncheckedAccount<'info>,
+}
+
+/// Whether a note with `nullifier_hash` was already withdrawn from a pool of this
+/// depth. Nullifiers are shared by every pool of one depth, so `merkle` only picks the
+/// depth, a `true` may come from a withdrawal out of another mint's pool. Read-only,
+/// meant to be simulated, the answer comes back as return data.
+pub fn is_nullifier_spent(
+    ctx: Context<IsNullifierSpentContext>,
+    _args: IsNullifierSpentArgs,
+) -> Result<bool> {
+    let nullifier_hash = &ctx.accounts.nullifier_hash;
+    Ok(nullifier_hash.owner == ctx.program_id && !nullifier_hash.data_is_empty())
+}
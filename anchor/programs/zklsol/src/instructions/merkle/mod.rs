@@ -1,13 +1,29 @@
 pub mod close_pda_account;
+pub mod configure_confidential_pool;
 pub mod create_merkle;
 pub mod create_merkle_node;
 pub mod deposit;
 pub mod dump_proof;
+pub mod is_known_root;
+pub mod is_nullifier_spent;
+pub mod pool_info;
+pub mod upgrade_pending_proof;
+pub mod verifying_key_info;
 pub mod withdraw;
+pub mod withdraw_batch;
+pub mod withdraw_confidential;
 
 pub use close_pda_account::*;
+pub use configure_confidential_pool::*;
 pub use create_merkle::*;
 pub use create_merkle_node::*;
 pub use deposit::*;
 pub use dump_proof::*;
+pub use is_known_root::*;
+pub use is_nullifier_spent::*;
+pub use pool_info::*;
+pub use upgrade_pending_proof::*;
+pub use verifying_key_info::*;
 pub use withdraw::*;
+pub use withdraw_batch::*;
+pub use withdraw_confidential::*;
@@ -0,0 +1,40 @@
+use crate::state::merkle_state::MerkleState;
+use crate::withdraw_verifier::ProofSystem;
+use anchor_lang::prelude::*;
+
+/// Return data of `pool_info`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolInfo {
+    pub mint: Pubkey,
+    pub depth: u64,
+    pub next_index: u64,
+    pub deposit_size: u64,
+    /// Root after the latest deposit, zero for a pool without any.
+    pub current_root: [u8; 32],
+    pub number_of_deposits: u64,
+    pub proof_system: ProofSystem,
+}
+
+impl From<&MerkleState> for PoolInfo {
+    fn from(merkle: &MerkleState) -> Self {
+        Self {
+            mint: merkle.mint,
+            depth: merkle.depth,
+            next_index: merkle.next_index,
+            deposit_size: merkle.deposit_size,
+            current_root: merkle.current_root().unwrap_or_default(),
+            number_of_deposits: merkle.number_of_deposits,
+            proof_system: merkle.proof_system,
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct PoolInfoContext<'info> {
+    pub merkle: Box<Account<'info, MerkleState>>,
+}
+
+/// Read-only, meant to be simulated, the answer comes back as return data.
+pub fn pool_info(ctx: Context<PoolInfoContext>) -> Result<PoolInfo> {
+    Ok(PoolInfo::from(&**ctx.accounts.merkle))
+}
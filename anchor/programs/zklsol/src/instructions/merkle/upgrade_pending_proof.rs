@@ -0,0 +1,65 @@
+use crate::error::ErrorCodes;
+use crate::state::merkle_state::{MerklePendingProofState, MerkleState};
+use crate::utils::transfer_sol_from_pda;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpgradePendingProof<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+    seeds = [MerkleState::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    bump = merkle.bump
+    )]
+    pub merkle: Box<Account<'info, MerkleState>>,
+    /// CHECK: mint of the pool, checked by the merkle seeds
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: pending proof in the legacy layout, decoded and checked against its seeds
+    #[account(mut, owner = crate::ID)]
+    pub pending_proof: UncheckedAccount<'info>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Rewrites a pending proof left over from before the `ChangedNode` layout so
+/// `dump_proof` can write it out. The freed rent goes to the signer.
+pub fn upgrade_pending_proof(ctx: Context<UpgradePendingProof>) -> Result<()> {
+    let merkle = &ctx.accounts.merkle;
+    let mut pending_proof_info = ctx.accounts.pending_proof.to_account_info();
+    let pending_proof =
+        MerklePendingProofState::from_legacy(&pending_proof_info.try_borrow_data()?)?;
+    require_eq!(
+        pending_proof.depth,
+        merkle.depth,
+        ErrorCodes::InvalidMerkleDepth
+    );
+    require_keys_eq!(pending_proof.mint, merkle.mint, ErrorCodes::WrongPdaAddress);
+    let expected_pda = Pubkey::create_program_address(
+        &[
+            MerklePendingProofState::SEED.as_bytes(),
+            pending_proof.mint.as_ref(),
+            pending_proof.depth.to_le_bytes().as_ref(),
+            pending_proof.index.to_le_bytes().as_ref(),
+            &[pending_proof.bump],
+        ],
+        ctx.program_id,
+    )
+    .map_err(|_| ErrorCodes::WrongPdaAddress)?;
+    require_keys_eq!(
+        expected_pda,
+        pending_proof_info.key(),
+        ErrorCodes::WrongPdaAddress
+    );
+
+    let size = MerklePendingProofState::size(pending_proof.depth);
+    pending_proof_info.realloc(size, true)?;
+    pending_proof.try_serialize(&mut &mut pending_proof_info.try_borrow_mut_data()?[..])?;
+    let surplus = pending_proof_info
+        .lamports()
+        .saturating_sub(ctx.accounts.rent.minimum_balance(size));
+    transfer_sol_from_pda(
+        &mut pending_proof_info,
+        &mut ctx.accounts.signer.to_account_info(),
+        surplus,
+    )?;
+    Ok(())
+}
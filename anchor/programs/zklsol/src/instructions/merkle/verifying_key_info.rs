@@ -0,0 +1,39 @@
+use crate::state::merkle_state::MerkleState;
+use crate::withdraw_verifier::ProofSystem;
+use anchor_lang::prelude::*;
+
+/// Return data of `verifying_key_info`, the keys pinned at `create_merkle`. Hashes
+/// are `WithdrawVerifyingKey::hash`, zero where the program had no key.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKeyInfo {
+    pub proof_system: ProofSystem,
+    /// See `withdraw_verifier::circuit_id`.
+    pub circuit_id: [u8; 32],
+    pub verifying_key_hash: [u8; 32],
+    /// False once the program's key for this pool no longer matches the pinned one,
+    /// withdrawals then fail with `VerifyingKeyMismatch`.
+    pub verifying_key_matches: bool,
+    pub deposit_verifying_key_hash: [u8; 32],
+}
+
+impl From<&MerkleState> for VerifyingKeyInfo {
+    fn from(merkle: &MerkleState) -> Self {
+        Self {
+            proof_system: merkle.proof_system,
+            circuit_id: merkle.circuit_id,
+            verifying_key_hash: merkle.verifying_key_hash,
+            verifying_key_matches: merkle.verifying_key().is_ok(),
+            deposit_verifying_key_hash: merkle.deposit_verifying_key_hash,
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct VerifyingKeyInfoContext<'info> {
+    pub merkle: Box<Account<'info, MerkleState>>,
+}
+
+/// Read-only, meant to be simulated, the answer comes back as return data.
+pub fn verifying_key_info(ctx: Context<VerifyingKeyInfoContext>) -> Result<VerifyingKeyInfo> {
+    Ok(VerifyingKeyInfo::from(&**ctx.accounts.merkle))
+}
@@ -1,21 +1,15 @@
 use crate::error::ErrorCodes;
 use crate::state::merkle_state::{MerkleState, NullifierHash};
-use crate::utils::{change_endianness, is_native, transfer_sol_from_pda, transfer_token_pda};
-use crate::withdraw20_verifying_key::VERIFYINGKEY as VERIFYINGKEY20;
-use crate::withdraw3_verifying_key::VERIFYINGKEY as VERIFYINGKEY3;
+use crate::utils::{is_native, transfer_sol_from_pda, transfer_token_pda};
+use crate::withdraw_verifier::{verify_withdraw, withdraw_public_inputs, ProofEncoding};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token, TokenAccount};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
-use groth16_solana::groth16::Groth16Verifier;
-use std::ops::Neg;
-
-type G1 = ark_bn254::g1::G1Affine;
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct WithdrawArgs {
     pub nullifier_hash: [u8; 32],
-    pub proof: [u8; 256],
+    pub proof: ProofEncoding,
     pub root: [u8; 32],
 }
 
@@ -26,7 +20,7 @@ pub struct WithdrawContext<'info> {
     pub signer: Signer<'info>,
     #[account(mut,
     has_one = mint,
-    seeds = [MerkleState::SEED.as_bytes().as_ref(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleState::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
     bump = merkle.bump
     )]
     pub merkle: Box<Account<'info, MerkleState>>,
@@ -34,7 +28,7 @@ pub struct WithdrawContext<'info> {
     mut,
     token::mint = mint,
     token::authority = merkle,
-    seeds = [MerkleState::TOKEN_SEED.as_bytes().as_ref(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    seeds = [MerkleState::TOKEN_SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
     bump
     )]
     pub merkle_token_account: Box<Account<'info, TokenAccount>>,
@@ -51,7 +45,7 @@ pub struct WithdrawContext<'info> {
     #[account(init,
     payer = signer,
     space = NullifierHash::SIZE,
-    seeds = [NullifierHash::SEED.as_bytes().as_ref(), merkle.depth.to_le_bytes().as_ref(), args.nullifier_hash.as_slice()],
+    seeds = [NullifierHash::SEED.as_bytes(), merkle.depth.to_le_bytes().as_ref(), args.nullifier_hash.as_slice()],
     bump
     )]
     pub nullifier_hash: Box<Account<'info, NullifierHash>>,
@@ -70,7 +64,7 @@ pub fn withdraw(ctx: Context<WithdrawContext>, args: WithdrawArgs) -> Result<()>
     let merkle_token_account = &ctx.accounts.merkle_token_account;
     let mint = &ctx.accounts.mint;
     let recipient = &ctx.accounts.recipient;
-    let m = merkle.to_merkle_tree();
+    let m = merkle.to_merkle_tree()?;
 
     require!(m.known_root(&args.root), ErrorCodes::MerkleErrorUnknownRoot);
     require_keys_neq!(
@@ -78,46 +72,9 @@ pub fn withdraw(ctx: Context<WithdrawContext>, args: WithdrawArgs) -> Result<()>
         recipient.key(),
         ErrorCodes::SignerCantMatchRecipient
     );
-    let proof = args.proof;
-    let public_input = [recipient.key.to_bytes(), args.nullifier_hash];
-    let proof_a: G1 = G1::deserialize_with_mode(
-        &*[&change_endianness(&proof[0..64]), &[0u8][..]].concat(),
-        Compress::No,
-        Validate::Yes,
-    )
-    .map_err(|_| ErrorCodes::DeserializeWithMode)?;
-    let mut proof_a_neg = [0u8; 65];
-    proof_a
-        .neg()
-        .x
-        .serialize_with_mode(&mut proof_a_neg[..32], Compress::No)
-        .map_err(|_| ErrorCodes::SerializeWithMode)?;
-    proof_a
-        .neg()
-        .y
-        .serialize_with_mode(&mut proof_a_neg[32..], Compress::No)
-        .map_err(|_| ErrorCodes::SerializeWithMode)?;
-    let proof_a = change_endianness(&proof_a_neg[..64])
-        .try_into()
-        .map_err(|_| ErrorCodes::ExtractProofA)?;
-    let proof_b = proof[64..192]
-        .try_into()
-        .map_err(|_| ErrorCodes::ExtractProofB)?;
-    let proof_c = proof[192..256]
-        .try_into()
-        .map_err(|_| ErrorCodes::ExtractProofC)?;
-    let mut verifier = if merkle.depth == 3 {
-        Groth16Verifier::new(&proof_a, &proof_b, &proof_c, &public_input, &VERIFYINGKEY3)
-            .map_err(|_| ErrorCodes::Groth16CreateError)?
-    } else if merkle.depth == 20 {
-        Groth16Verifier::new(&proof_a, &proof_b, &proof_c, &public_input, &VERIFYINGKEY20)
-            .map_err(|_| ErrorCodes::Groth16CreateError)?
-    } else {
-        return Err(ErrorCodes::InvalidMerkleDepth.into());
-    };
-    verifier
-        .verify()
-        .map_err(|_| ErrorCodes::Groth16VerifyError)?;
+    let public_input = withdraw_public_inputs(recipient.key, &args.nullifier_hash);
+    let verifying_key = merkle.verifying_key()?;
+    verify_withdraw(&args.proof, &public_input, &verifying_key.key)?;
     if is_native(&mint.to_account_info()) {
         transfer_sol_from_pda(
             &mut merkle.to_account_info(),
@@ -125,10 +82,12 @@ pub fn withdraw(ctx: Context<WithdrawContext>, args: WithdrawArgs) -> Result<()>
             merkle.deposit_size,
         )?;
     } else {
-        let binding = merkle.depth.to_le_bytes();
+        let mint_key = mint.key();
+        let depth_binding = merkle.depth.to_le_bytes();
         let seeds = [
-            MerkleState::SEED.as_bytes().as_ref(),
-            binding.as_ref(),
+            MerkleState::SEED.as_bytes(),
+            mint_key.as_ref(),
+            depth_binding.as_ref(),
             &[merkle.bump],
         ];
         transfer_token_pda(
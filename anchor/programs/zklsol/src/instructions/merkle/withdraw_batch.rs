@@ -0,0 +1,162 @@
+use crate::error::ErrorCodes;
+use crate::instructions::WithdrawArgs;
+use crate::state::merkle_state::{MerkleState, NullifierHash};
+use crate::utils::{
+    assert_ata, create_pda_account, is_native, transfer_sol_from_pda, transfer_token_pda,
+};
+use crate::withdraw_verifier::{
+    verify_withdraw_batch, withdraw_public_inputs, ProofEncoding, MAX_BATCH_WITHDRAWALS,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// `remaining_accounts` per withdrawal, in order: recipient, its associated token
+/// account for the pool mint (must exist for SPL pools) and the nullifier hash PDA.
+pub const BATCH_WITHDRAWAL_ACCOUNTS: usize = 3;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct WithdrawBatchArgs {
+    pub withdrawals: Vec<WithdrawArgs>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBatchContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mut,
+    has_one = mint,
+    seeds = [MerkleState::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    bump = merkle.bump
+    )]
+    pub merkle: Box<Account<'info, MerkleState>>,
+    #[account(
+    mut,
+    token::mint = mint,
+    token::authority = merkle,
+    seeds = [MerkleState::TOKEN_SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    bump
+    )]
+    pub merkle_token_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub mint: Box<Account<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Same checks and payout as `withdraw` for up to `MAX_BATCH_WITHDRAWALS` proofs,
+/// verified together with `verify_withdraw_batch`.
+pub fn withdraw_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawBatchContext<'info>>,
+    args: WithdrawBatchArgs,
+) -> Result<()> {
+    let signer = &ctx.accounts.signer;
+    let merkle = &mut ctx.accounts.merkle;
+    let token_program = &ctx.accounts.token_program;
+    let merkle_token_account = &ctx.accounts.merkle_token_account;
+    let mint = &ctx.accounts.mint;
+    let system_program = &ctx.accounts.system_program;
+    let program_id = ctx.program_id;
+    let count = args.withdrawals.len();
+    require!(
+        count > 0 && count <= MAX_BATCH_WITHDRAWALS,
+        ErrorCodes::InvalidBatchSize
+    );
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        count * BATCH_WITHDRAWAL_ACCOUNTS,
+        ErrorCodes::InvalidRemainingAccounts
+    );
+    let m = merkle.to_merkle_tree()?;
+
+    let mut public_inputs = Vec::with_capacity(count);
+    for (withdrawal, accounts) in args
+        .withdrawals
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(BATCH_WITHDRAWAL_ACCOUNTS))
+    {
+        let recipient = &accounts[0];
+        require!(
+            m.known_root(&withdrawal.root),
+            ErrorCodes::MerkleErrorUnknownRoot
+        );
+        require_keys_neq!(
+            *signer.key,
+            *recipient.key,
+            ErrorCodes::SignerCantMatchRecipient
+        );
+        assert_ata(accounts[1].key, recipient.key, &mint.key())?;
+        public_inputs.push(withdraw_public_inputs(
+            recipient.key,
+            &withdrawal.nullifier_hash,
+        ));
+    }
+    let proofs: Vec<ProofEncoding> = args
+        .withdrawals
+        .iter()
+        .map(|withdrawal| withdrawal.proof.clone())
+        .collect();
+    let verifying_key = merkle.verifying_key()?;
+    verify_withdraw_batch(&proofs, &public_inputs, &verifying_key.key)?;
+
+    let depth_binding = merkle.depth.to_le_bytes();
+    let mint_key = mint.key();
+    for (withdrawal, accounts) in args
+        .withdrawals
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(BATCH_WITHDRAWAL_ACCOUNTS))
+    {
+        let (recipient, recipient_token_account, nullifier_hash) =
+            (&accounts[0], &accounts[1], &accounts[2]);
+        // Also catches the same nullifier twice in one batch.
+        require!(
+            nullifier_hash.data_is_empty(),
+            ErrorCodes::NullifierAlreadySpent
+        );
+        let seeds = [
+            NullifierHash::SEED.as_bytes(),
+            depth_binding.as_ref(),
+            withdrawal.nullifier_hash.as_slice(),
+        ];
+        create_pda_account(
+            nullifier_hash,
+            &system_program.to_account_info(),
+            &signer.to_account_info(),
+            NullifierHash::SIZE,
+            program_id,
+            &seeds,
+        )?;
+        let (_, bump) =
+            NullifierHash::find_address(merkle.depth, &withdrawal.nullifier_hash, program_id);
+        NullifierHash {
+            bump,
+            nullifier_hash: withdrawal.nullifier_hash,
+        }
+        .try_serialize(&mut &mut nullifier_hash.data.borrow_mut()[..])?;
+
+        if is_native(&mint.to_account_info()) {
+            transfer_sol_from_pda(
+                &mut merkle.to_account_info(),
+                &mut recipient.clone(),
+                merkle.deposit_size,
+            )?;
+        } else {
+            let seeds = [
+                MerkleState::SEED.as_bytes(),
+                mint_key.as_ref(),
+                depth_binding.as_ref(),
+                &[merkle.bump],
+            ];
+            transfer_token_pda(
+                merkle_token_account.to_account_info(),
+                recipient_token_account.clone(),
+                token_program.to_account_info(),
+                merkle.to_account_info(),
+                merkle.deposit_size,
+                &[seeds.as_slice()],
+            )?;
+        }
+    }
+
+    merkle.number_of_deposits -= count as u64;
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::confidential_balance::{
+    pool_confidential_account, pool_payout_instructions, DECRYPTABLE_BALANCE_SIZE,
+};
+use crate::error::ErrorCodes;
+use crate::state::merkle_state::{MerkleState, NullifierHash};
+use crate::withdraw_verifier::{verify_withdraw, withdraw_public_inputs, ProofEncoding};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct WithdrawConfidentialArgs {
+    pub nullifier_hash: [u8; 32],
+    pub proof: ProofEncoding,
+    pub root: [u8; 32],
+    /// The pool's available balance once the payout is sent, encrypted with the key of
+    /// `POOL_AE_KEY_SEED`.
+    pub new_decryptable_available_balance: [u8; DECRYPTABLE_BALANCE_SIZE],
+}
+
+#[derive(Accounts)]
+#[instruction(args: WithdrawConfidentialArgs)]
+pub struct WithdrawConfidentialContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mut,
+    has_one = mint,
+    seeds = [MerkleState::SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    bump = merkle.bump
+    )]
+    pub merkle: Box<Account<'info, MerkleState>>,
+    #[account(
+    mut,
+    token::mint = mint,
+    token::authority = merkle,
+    seeds = [MerkleState::TOKEN_SEED.as_bytes(), mint.key().as_ref(), merkle.depth.to_le_bytes().as_ref()],
+    bump
+    )]
+    pub merkle_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Check can be any account
+    pub recipient: UncheckedAccount<'info>,
+    /// Already configured for confidential transfers by the recipient.
+    #[account(
+    mut,
+    associated_token::mint = mint,
+    associated_token::authority = recipient,
+    associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Token-2022 checks it holds a transfer proof from the pool to the recipient
+    pub proof_context: UncheckedAccount<'info>,
+    #[account(init,
+    payer = signer,
+    space = NullifierHash::SIZE,
+    seeds = [NullifierHash::SEED.as_bytes(), merkle.depth.to_le_bytes().as_ref(), args.nullifier_hash.as_slice()],
+    bump
+    )]
+    pub nullifier_hash: Box<Account<'info, NullifierHash>>,
+    pub token_program: Program<'info, Token2022>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// `withdraw` for Token-2022 pools. The pool moves the payout into its confidential
+/// balance and sends it with a confidential transfer, so it lands in the recipient's
+/// pending balance without the recipient signing and without its public balance moving.
+///
+/// The transfer proof is made against the pool's available balance after this payout's
+/// deposit is applied. Any other payout or confidential credit to the pool landing first
+/// changes that balance, and the withdrawal has to be retried with a new proof.
+pub fn withdraw_confidential(
+    ctx: Context<WithdrawConfidentialContext>,
+    args: WithdrawConfidentialArgs,
+) -> Result<()> {
+    let signer = &ctx.accounts.signer;
+    let token_program = &ctx.accounts.token_program;
+    let recipient_token_account = &ctx.accounts.recipient_token_account;
+    let merkle_token_account = &ctx.accounts.merkle_token_account;
+    let proof_context = &ctx.accounts.proof_context;
+    let mint = &ctx.accounts.mint;
+    let recipient = &ctx.accounts.recipient;
+    let nullifier_hash = &mut ctx.accounts.nullifier_hash;
+    let merkle = &mut ctx.accounts.merkle;
+    let m = merkle.to_merkle_tree()?;
+
+    require!(m.known_root(&args.root), ErrorCodes::MerkleErrorUnknownRoot);
+    require_keys_neq!(
+        *signer.key,
+        recipient.key(),
+        ErrorCodes::SignerCantMatchRecipient
+    );
+    let public_input = withdraw_public_inputs(recipient.key, &args.nullifier_hash);
+    let verifying_key = merkle.verifying_key()?;
+    verify_withdraw(&args.proof, &public_input, &verifying_key.key)?;
+    nullifier_hash.bump = ctx.bumps.nullifier_hash;
+    nullifier_hash.nullifier_hash = args.nullifier_hash;
+
+    let pool = pool_confidential_account(&merkle_token_account.to_account_info().data.borrow())?;
+    let mint_key = mint.key();
+    let depth_binding = merkle.depth.to_le_bytes();
+    let seeds = [
+        MerkleState::SEED.as_bytes(),
+        mint_key.as_ref(),
+        depth_binding.as_ref(),
+        &[merkle.bump],
+    ];
+    let [deposit, apply_pending_balance, transfer] = pool_payout_instructions(
+        &merkle_token_account.key(),
+        &mint_key,
+        &recipient_token_account.key(),
+        proof_context.key,
+        &merkle.key(),
+        &pool,
+        merkle.deposit_size,
+        mint.decimals,
+        &args.new_decryptable_available_balance,
+    )?;
+    solana_program::program::invoke_signed(
+        &deposit,
+        &[
+            merkle_token_account.to_account_info(),
+            mint.to_account_info(),
+            merkle.to_account_info(),
+            token_program.to_account_info(),
+        ],
+        &[seeds.as_slice()],
+    )?;
+    solana_program::program::invoke_signed(
+        &apply_pending_balance,
+        &[
+            merkle_token_account.to_account_info(),
+            merkle.to_account_info(),
+            token_program.to_account_info(),
+        ],
+        &[seeds.as_slice()],
+    )?;
+    solana_program::program::invoke_signed(
+        &transfer,
+        &[
+            merkle_token_account.to_account_info(),
+            mint.to_account_info(),
+            recipient_token_account.to_account_info(),
+            proof_context.to_account_info(),
+            merkle.to_account_info(),
+            token_program.to_account_info(),
+        ],
+        &[seeds.as_slice()],
+    )?;
+    merkle.number_of_deposits -= 1;
+    Ok(())
+}
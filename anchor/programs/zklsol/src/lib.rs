@@ -1,10 +1,18 @@
 #![allow(unused_imports)]
-mod error;
-mod instructions;
-mod state;
+pub mod confidential_balance;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod plonk_verifier;
+#[cfg(not(target_os = "solana"))]
+pub mod preflight;
+pub mod state;
+#[cfg(test)]
+mod tests;
 mod utils;
 mod withdraw20_verifying_key;
 mod withdraw3_verifying_key;
+pub mod withdraw_verifier;
 
 use anchor_lang::prelude::*;
 use instructions::*;
@@ -23,6 +31,10 @@ pub mod zklsol {
         dump_proof::dump_proof(ctx)
     }
 
+    pub fn upgrade_pending_proof(ctx: Context<UpgradePendingProof>) -> Result<()> {
+        upgrade_pending_proof::upgrade_pending_proof(ctx)
+    }
+
     pub fn deactivate_address_lookup_table(
         ctx: Context<DeactivateAddressLookupTable>,
     ) -> Result<()> {
@@ -47,14 +59,54 @@ pub mod zklsol {
         withdraw::withdraw(ctx, args)
     }
 
+    pub fn withdraw_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawBatchContext<'info>>,
+        args: WithdrawBatchArgs,
+    ) -> Result<()> {
+        withdraw_batch::withdraw_batch(ctx, args)
+    }
+
+    pub fn withdraw_confidential(
+        ctx: Context<WithdrawConfidentialContext>,
+        args: WithdrawConfidentialArgs,
+    ) -> Result<()> {
+        withdraw_confidential::withdraw_confidential(ctx, args)
+    }
+
     pub fn deposit(ctx: Context<DepositContext>, args: DepositArgs) -> Result<()> {
         deposit::deposit(ctx, args)
     }
 
+    pub fn is_known_root(ctx: Context<IsKnownRootContext>, args: IsKnownRootArgs) -> Result<bool> {
+        is_known_root::is_known_root(ctx, args)
+    }
+
+    pub fn is_nullifier_spent(
+        ctx: Context<IsNullifierSpentContext>,
+        args: IsNullifierSpentArgs,
+    ) -> Result<bool> {
+        is_nullifier_spent::is_nullifier_spent(ctx, args)
+    }
+
+    pub fn pool_info(ctx: Context<PoolInfoContext>) -> Result<PoolInfo> {
+        pool_info::pool_info(ctx)
+    }
+
+    pub fn verifying_key_info(ctx: Context<VerifyingKeyInfoContext>) -> Result<VerifyingKeyInfo> {
+        verifying_key_info::verifying_key_info(ctx)
+    }
+
     pub fn create_merkle(ctx: Context<CreateMerkle>, args: CreateMerkleArgs) -> Result<()> {
         create_merkle::create_merkle(ctx, args)
     }
 
+    pub fn configure_confidential_pool(
+        ctx: Context<ConfigureConfidentialPoolContext>,
+        args: ConfigureConfidentialPoolArgs,
+    ) -> Result<()> {
+        configure_confidential_pool::configure_confidential_pool(ctx, args)
+    }
+
     pub fn create_merkle_node(
         ctx: Context<CreateMerkleNode>,
         args: CreateMerkleNodeArgs,
@@ -0,0 +1,418 @@
+//! Verifier for snarkjs PLONK proofs, a universal setup alternative to Groth16.
+//! Follows `plonk_verify.js` step by step, with the curve operations going
+//! through the alt_bn128 syscalls and the transcript through keccak.
+use crate::error::ErrorCodes;
+use anchor_lang::solana_program::keccak;
+use ark_bn254::{Fq, Fr};
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use solana_bn254::prelude::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+
+/// A, B, C, Z, T1, T2, T3, Wxi, Wxiw, then eval_a, eval_b, eval_c, eval_s1, eval_s2, eval_zw.
+pub const PLONK_PROOF_SIZE: usize = 9 * 64 + 6 * 32;
+
+const G1_GENERATOR: [u8; 64] = {
+    let mut point = [0u8; 64];
+    point[31] = 1;
+    point[63] = 2;
+    point
+};
+
+/// x.c1, x.c0, y.c1, y.c0 big-endian, the layout `alt_bn128_pairing` expects.
+pub const G2_GENERATOR: [u8; 128] = [
+    25, 142, 147, 147, 146, 13, 72, 58, 114, 96, 191, 183, 49, 251, 93, 37, 241, 170, 73, 51, 53,
+    169, 231, 18, 151, 228, 133, 183, 174, 243, 18, 194, 24, 0, 222, 239, 18, 31, 30, 118, 66, 106,
+    0, 102, 94, 92, 68, 121, 103, 67, 34, 212, 247, 94, 218, 221, 70, 222, 189, 92, 217, 146, 246,
+    237, 9, 6, 137, 208, 88, 95, 240, 117, 236, 158, 153, 173, 105, 12, 51, 149, 188, 75, 49, 51,
+    112, 179, 142, 243, 85, 172, 218, 220, 209, 34, 151, 91, 18, 200, 94, 165, 219, 140, 109, 235,
+    74, 171, 113, 128, 141, 203, 64, 143, 227, 209, 231, 105, 12, 67, 211, 123, 76, 230, 204, 1,
+    102, 250, 125, 170,
+];
+
+/// `verification_key.json` of `snarkjs plonk setup`, points big-endian like `Groth16Verifyingkey`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlonkVerifyingKey {
+    pub n_public: usize,
+    /// log2 of the domain size.
+    pub power: u32,
+    pub k1: [u8; 32],
+    pub k2: [u8; 32],
+    /// Root of unity of order `2^power`.
+    pub w: [u8; 32],
+    pub qm: [u8; 64],
+    pub ql: [u8; 64],
+    pub qr: [u8; 64],
+    pub qo: [u8; 64],
+    pub qc: [u8; 64],
+    pub s1: [u8; 64],
+    pub s2: [u8; 64],
+    pub s3: [u8; 64],
+    pub x_2: [u8; 128],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlonkProof {
+    pub a: [u8; 64],
+    pub b: [u8; 64],
+    pub c: [u8; 64],
+    pub z: [u8; 64],
+    pub t1: [u8; 64],
+    pub t2: [u8; 64],
+    pub t3: [u8; 64],
+    pub wxi: [u8; 64],
+    pub wxiw: [u8; 64],
+    pub eval_a: [u8; 32],
+    pub eval_b: [u8; 32],
+    pub eval_c: [u8; 32],
+    pub eval_s1: [u8; 32],
+    pub eval_s2: [u8; 32],
+    pub eval_zw: [u8; 32],
+}
+
+impl PlonkProof {
+    pub fn from_bytes(bytes: &[u8; PLONK_PROOF_SIZE]) -> Self {
+        let point = |i: usize| -> [u8; 64] { bytes[i * 64..(i + 1) * 64].try_into().unwrap() };
+        let scalar = |i: usize| -> [u8; 32] {
+            let start = 9 * 64 + i * 32;
+            bytes[start..start + 32].try_into().unwrap()
+        };
+        Self {
+            a: point(0),
+            b: point(1),
+            c: point(2),
+            z: point(3),
+            t1: point(4),
+            t2: point(5),
+            t3: point(6),
+            wxi: point(7),
+            wxiw: point(8),
+            eval_a: scalar(0),
+            eval_b: scalar(1),
+            eval_c: scalar(2),
+            eval_s1: scalar(3),
+            eval_s2: scalar(4),
+            eval_zw: scalar(5),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; PLONK_PROOF_SIZE] {
+        let mut output = [0u8; PLONK_PROOF_SIZE];
+        let points = [
+            &self.a, &self.b, &self.c, &self.z, &self.t1, &self.t2, &self.t3, &self.wxi, &self.wxiw,
+        ];
+        for (i, point) in points.iter().enumerate() {
+            output[i * 64..(i + 1) * 64].copy_from_slice(*point);
+        }
+        let scalars = [
+            &self.eval_a,
+            &self.eval_b,
+            &self.eval_c,
+            &self.eval_s1,
+            &self.eval_s2,
+            &self.eval_zw,
+        ];
+        for (i, scalar) in scalars.iter().enumerate() {
+            let start = 9 * 64 + i * 32;
+            output[start..start + 32].copy_from_slice(*scalar);
+        }
+        output
+    }
+}
+
+/// Proof evaluations as field elements.
+pub struct PlonkEvaluations {
+    pub a: Fr,
+    pub b: Fr,
+    pub c: Fr,
+    pub s1: Fr,
+    pub s2: Fr,
+    pub zw: Fr,
+}
+
+impl PlonkEvaluations {
+    pub fn new(proof: &PlonkProof) -> Result<Self, ErrorCodes> {
+        Ok(Self {
+            a: fr_from_be(&proof.eval_a)?,
+            b: fr_from_be(&proof.eval_b)?,
+            c: fr_from_be(&proof.eval_c)?,
+            s1: fr_from_be(&proof.eval_s1)?,
+            s2: fr_from_be(&proof.eval_s2)?,
+            zw: fr_from_be(&proof.eval_zw)?,
+        })
+    }
+}
+
+/// Fiat-Shamir challenges, `v[0]` is unused to keep the snarkjs numbering.
+pub struct PlonkChallenges {
+    pub beta: Fr,
+    pub gamma: Fr,
+    pub alpha: Fr,
+    pub xi: Fr,
+    pub v: [Fr; 6],
+    pub u: Fr,
+    /// xi^n
+    pub xin: Fr,
+    /// xi^n - 1, the vanishing polynomial at xi.
+    pub zh: Fr,
+}
+
+impl PlonkChallenges {
+    pub fn new(
+        proof: &PlonkProof,
+        evaluations: &PlonkEvaluations,
+        public_inputs: &[Fr],
+        vk: &PlonkVerifyingKey,
+    ) -> Self {
+        let mut transcript = Transcript::default();
+        for point in [
+            &vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3,
+        ] {
+            transcript.add_point(point);
+        }
+        for input in public_inputs {
+            transcript.add_scalar(input);
+        }
+        transcript.add_point(&proof.a);
+        transcript.add_point(&proof.b);
+        transcript.add_point(&proof.c);
+        let beta = transcript.challenge();
+
+        transcript.add_scalar(&beta);
+        let gamma = transcript.challenge();
+
+        transcript.add_scalar(&beta);
+        transcript.add_scalar(&gamma);
+        transcript.add_point(&proof.z);
+        let alpha = transcript.challenge();
+
+        transcript.add_scalar(&alpha);
+        transcript.add_point(&proof.t1);
+        transcript.add_point(&proof.t2);
+        transcript.add_point(&proof.t3);
+        let xi = transcript.challenge();
+
+        transcript.add_scalar(&xi);
+        for evaluation in [
+            &evaluations.a,
+            &evaluations.b,
+            &evaluations.c,
+            &evaluations.s1,
+            &evaluations.s2,
+            &evaluations.zw,
+        ] {
+            transcript.add_scalar(evaluation);
+        }
+        let v1 = transcript.challenge();
+        let mut v = [Fr::zero(); 6];
+        let mut power = Fr::one();
+        for vi in v.iter_mut().skip(1) {
+            power *= v1;
+            *vi = power;
+        }
+
+        transcript.add_point(&proof.wxi);
+        transcript.add_point(&proof.wxiw);
+        let u = transcript.challenge();
+
+        let mut xin = xi;
+        for _ in 0..vk.power {
+            xin.square_in_place();
+        }
+        Self {
+            beta,
+            gamma,
+            alpha,
+            xi,
+            v,
+            u,
+            xin,
+            zh: xin - Fr::one(),
+        }
+    }
+}
+
+/// snarkjs' `Keccak256Transcript`, cleared after every challenge.
+#[derive(Default)]
+struct Transcript {
+    data: Vec<u8>,
+}
+
+impl Transcript {
+    fn add_point(&mut self, point: &[u8; 64]) {
+        self.data.extend_from_slice(point);
+    }
+
+    fn add_scalar(&mut self, scalar: &Fr) {
+        self.data.extend_from_slice(&fr_to_be(scalar));
+    }
+
+    fn challenge(&mut self) -> Fr {
+        let hash = keccak::hash(&self.data);
+        self.data.clear();
+        Fr::from_be_bytes_mod_order(&hash.to_bytes())
+    }
+}
+
+pub fn fr_to_be(value: &Fr) -> [u8; 32] {
+    let bytes = value.into_bigint().to_bytes_be();
+    let mut output = [0u8; 32];
+    output[32 - bytes.len()..].copy_from_slice(&bytes);
+    output
+}
+
+/// Rejects values that aren't reduced, like groth16-solana does for public inputs.
+pub fn fr_from_be(bytes: &[u8; 32]) -> Result<Fr, ErrorCodes> {
+    let value = Fr::from_be_bytes_mod_order(bytes);
+    if fr_to_be(&value) != *bytes {
+        return Err(ErrorCodes::PlonkInvalidScalar);
+    }
+    Ok(value)
+}
+
+/// L_1(xi) .. L_n(xi) for the public input rows, `L[0]` is L_1.
+pub fn lagrange_evaluations(
+    challenges: &PlonkChallenges,
+    vk: &PlonkVerifyingKey,
+) -> Result<Vec<Fr>, ErrorCodes> {
+    let w_root = fr_from_be(&vk.w)?;
+    let n = Fr::from(1u64 << vk.power);
+    let mut w = Fr::one();
+    let mut output = Vec::with_capacity(vk.n_public.max(1));
+    for _ in 0..vk.n_public.max(1) {
+        let denominator = (n * (challenges.xi - w))
+            .inverse()
+            .ok_or(ErrorCodes::PlonkVerifyError)?;
+        output.push(w * challenges.zh * denominator);
+        w *= w_root;
+    }
+    Ok(output)
+}
+
+pub fn public_input_evaluation(public_inputs: &[Fr], lagrange: &[Fr]) -> Fr {
+    public_inputs
+        .iter()
+        .zip(lagrange)
+        .fold(Fr::zero(), |pi, (input, l)| pi - *input * l)
+}
+
+pub fn r0(evaluations: &PlonkEvaluations, challenges: &PlonkChallenges, pi: Fr, l1: Fr) -> Fr {
+    let e2 = l1 * challenges.alpha.square();
+    let e3a = evaluations.a + challenges.beta * evaluations.s1 + challenges.gamma;
+    let e3b = evaluations.b + challenges.beta * evaluations.s2 + challenges.gamma;
+    let e3c = evaluations.c + challenges.gamma;
+    let e3 = e3a * e3b * e3c * evaluations.zw * challenges.alpha;
+    pi - e2 - e3
+}
+
+fn g1_add(p: &[u8; 64], q: &[u8; 64]) -> Result<[u8; 64], ErrorCodes> {
+    let input = [&p[..], &q[..]].concat();
+    let output = alt_bn128_addition(&input).map_err(|_| ErrorCodes::PlonkCurveError)?;
+    output.try_into().map_err(|_| ErrorCodes::PlonkCurveError)
+}
+
+fn g1_mul(p: &[u8; 64], scalar: &Fr) -> Result<[u8; 64], ErrorCodes> {
+    let input = [&p[..], &fr_to_be(scalar)[..]].concat();
+    let output = alt_bn128_multiplication(&input).map_err(|_| ErrorCodes::PlonkCurveError)?;
+    output.try_into().map_err(|_| ErrorCodes::PlonkCurveError)
+}
+
+fn g1_neg(p: &[u8; 64]) -> [u8; 64] {
+    if p == &[0u8; 64] {
+        return *p;
+    }
+    let y = -Fq::from_be_bytes_mod_order(&p[32..]);
+    let bytes = y.into_bigint().to_bytes_be();
+    let mut output = *p;
+    output[32..].fill(0);
+    output[64 - bytes.len()..].copy_from_slice(&bytes);
+    output
+}
+
+fn g1_sub(p: &[u8; 64], q: &[u8; 64]) -> Result<[u8; 64], ErrorCodes> {
+    g1_add(p, &g1_neg(q))
+}
+
+pub fn verify_plonk_proof(
+    proof: &PlonkProof,
+    public_inputs: &[[u8; 32]],
+    vk: &PlonkVerifyingKey,
+) -> Result<(), ErrorCodes> {
+    if public_inputs.len() != vk.n_public {
+        return Err(ErrorCodes::PlonkInvalidPublicInputs);
+    }
+    let public_inputs = public_inputs
+        .iter()
+        .map(fr_from_be)
+        .collect::<Result<Vec<Fr>, ErrorCodes>>()?;
+    let evaluations = PlonkEvaluations::new(proof)?;
+    let challenges = PlonkChallenges::new(proof, &evaluations, &public_inputs, vk);
+    let lagrange = lagrange_evaluations(&challenges, vk)?;
+    let pi = public_input_evaluation(&public_inputs, &lagrange);
+    let r0 = r0(&evaluations, &challenges, pi, lagrange[0]);
+    let (k1, k2) = (fr_from_be(&vk.k1)?, fr_from_be(&vk.k2)?);
+    let beta_xi = challenges.beta * challenges.xi;
+
+    // [D]
+    let mut d1 = g1_mul(&vk.qm, &(evaluations.a * evaluations.b))?;
+    d1 = g1_add(&d1, &g1_mul(&vk.ql, &evaluations.a)?)?;
+    d1 = g1_add(&d1, &g1_mul(&vk.qr, &evaluations.b)?)?;
+    d1 = g1_add(&d1, &g1_mul(&vk.qo, &evaluations.c)?)?;
+    d1 = g1_add(&d1, &vk.qc)?;
+    let d2a = (evaluations.a + beta_xi + challenges.gamma)
+        * (evaluations.b + beta_xi * k1 + challenges.gamma)
+        * (evaluations.c + beta_xi * k2 + challenges.gamma)
+        * challenges.alpha;
+    let d2b = lagrange[0] * challenges.alpha.square();
+    let d2 = g1_mul(&proof.z, &(d2a + d2b + challenges.u))?;
+    let d3 = g1_mul(
+        &vk.s3,
+        &((evaluations.a + challenges.beta * evaluations.s1 + challenges.gamma)
+            * (evaluations.b + challenges.beta * evaluations.s2 + challenges.gamma)
+            * challenges.alpha
+            * challenges.beta
+            * evaluations.zw),
+    )?;
+    let d4 = g1_add(
+        &proof.t1,
+        &g1_add(
+            &g1_mul(&proof.t2, &challenges.xin)?,
+            &g1_mul(&proof.t3, &challenges.xin.square())?,
+        )?,
+    )?;
+    let d4 = g1_mul(&d4, &challenges.zh)?;
+    let d = g1_sub(&g1_sub(&g1_add(&d1, &d2)?, &d3)?, &d4)?;
+
+    // [F]
+    let mut f = d;
+    for (point, v) in [&proof.a, &proof.b, &proof.c, &vk.s1, &vk.s2]
+        .iter()
+        .zip(&challenges.v[1..])
+    {
+        f = g1_add(&f, &g1_mul(point, v)?)?;
+    }
+
+    // [E]
+    let e = -r0
+        + challenges.v[1] * evaluations.a
+        + challenges.v[2] * evaluations.b
+        + challenges.v[3] * evaluations.c
+        + challenges.v[4] * evaluations.s1
+        + challenges.v[5] * evaluations.s2
+        + challenges.u * evaluations.zw;
+    let e = g1_mul(&G1_GENERATOR, &e)?;
+
+    // e(-A1, X_2) * e(B1, G2) == 1
+    let a1 = g1_add(&proof.wxi, &g1_mul(&proof.wxiw, &challenges.u)?)?;
+    let w = fr_from_be(&vk.w)?;
+    let mut b1 = g1_mul(&proof.wxi, &challenges.xi)?;
+    b1 = g1_add(
+        &b1,
+        &g1_mul(&proof.wxiw, &(challenges.u * challenges.xi * w))?,
+    )?;
+    b1 = g1_sub(&g1_add(&b1, &f)?, &e)?;
+    let input = [&g1_neg(&a1)[..], &vk.x_2[..], &b1[..], &G2_GENERATOR[..]].concat();
+    let result = alt_bn128_pairing(&input).map_err(|_| ErrorCodes::PlonkCurveError)?;
+    if result != fr_to_be(&Fr::one()) {
+        return Err(ErrorCodes::PlonkVerifyError);
+    }
+    Ok(())
+}
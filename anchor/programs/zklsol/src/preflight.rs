@@ -0,0 +1,141 @@
+//! Off-chain replay of the checks `withdraw` performs, so wallets and relayers
+//! can reject a withdrawal before paying for a transaction that is bound to fail.
+use crate::error::ErrorCodes;
+use crate::instructions::WithdrawArgs;
+use crate::state::merkle_state::MerkleState;
+use crate::withdraw_verifier::{
+    negate_proof_a, verify_withdraw, withdraw_public_inputs, ProofSystem,
+};
+use anchor_lang::prelude::Pubkey;
+
+#[derive(Debug, Clone, Copy)]
+pub enum CheckStatus {
+    Passed,
+    Failed(ErrorCodes),
+    /// Not evaluated because an earlier check it depends on failed,
+    /// or because it doesn't apply to the pool's proof system.
+    Skipped,
+}
+
+impl CheckStatus {
+    fn from_result<T>(result: Result<T, ErrorCodes>) -> Self {
+        match result {
+            Ok(_) => Self::Passed,
+            Err(e) => Self::Failed(e),
+        }
+    }
+
+    pub fn is_passed(&self) -> bool {
+        matches!(self, Self::Passed)
+    }
+
+    pub fn error(&self) -> Option<ErrorCodes> {
+        match self {
+            Self::Failed(e) => Some(*e),
+            _ => None,
+        }
+    }
+}
+
+pub struct PreflightInput<'a> {
+    pub args: &'a WithdrawArgs,
+    pub signer: &'a Pubkey,
+    pub recipient: &'a Pubkey,
+    pub merkle: &'a MerkleState,
+    /// Whether the `NullifierHash` account (see `NullifierHash::find_address`) already exists.
+    pub nullifier_account_exists: bool,
+}
+
+/// Outcome of every check, in the order `withdraw` runs them.
+#[derive(Debug, Clone)]
+pub struct PreflightReport {
+    pub merkle_state: CheckStatus,
+    pub known_root: CheckStatus,
+    pub signer_not_recipient: CheckStatus,
+    pub proof_encoding: CheckStatus,
+    pub proof_a: CheckStatus,
+    pub verifying_key: CheckStatus,
+    pub verification: CheckStatus,
+    pub nullifier_unspent: CheckStatus,
+}
+
+impl PreflightReport {
+    pub fn checks(&self) -> [(&'static str, CheckStatus); 8] {
+        [
+            ("merkle_state", self.merkle_state),
+            ("known_root", self.known_root),
+            ("signer_not_recipient", self.signer_not_recipient),
+            ("proof_encoding", self.proof_encoding),
+            ("proof_a", self.proof_a),
+            ("verifying_key", self.verifying_key),
+            ("verification", self.verification),
+            ("nullifier_unspent", self.nullifier_unspent),
+        ]
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.checks().iter().all(|(_, status)| status.is_passed())
+    }
+
+    /// The error the transaction would fail with, if any.
+    pub fn first_error(&self) -> Option<ErrorCodes> {
+        self.checks().iter().find_map(|(_, status)| status.error())
+    }
+}
+
+pub fn preflight_withdraw(input: &PreflightInput) -> PreflightReport {
+    let tree = input.merkle.to_merkle_tree();
+    let merkle_state = match &tree {
+        Ok(_) => CheckStatus::Passed,
+        Err(e) => CheckStatus::Failed(*e),
+    };
+    let known_root = match &tree {
+        Ok(tree) if tree.known_root(&input.args.root) => CheckStatus::Passed,
+        Ok(_) => CheckStatus::Failed(ErrorCodes::MerkleErrorUnknownRoot),
+        Err(_) => CheckStatus::Skipped,
+    };
+    let signer_not_recipient = if input.signer == input.recipient {
+        CheckStatus::Failed(ErrorCodes::SignerCantMatchRecipient)
+    } else {
+        CheckStatus::Passed
+    };
+    let proof_encoding = if input.args.proof.proof_system() != input.merkle.proof_system {
+        CheckStatus::Failed(ErrorCodes::UnsupportedProofSystem)
+    } else {
+        match input.merkle.proof_system {
+            ProofSystem::Groth16 => CheckStatus::from_result(input.args.proof.to_uncompressed()),
+            ProofSystem::Plonk => CheckStatus::Passed,
+        }
+    };
+    let proof_a = match input.args.proof.to_uncompressed() {
+        Ok(proof) if proof_encoding.is_passed() => CheckStatus::from_result(negate_proof_a(&proof)),
+        _ => CheckStatus::Skipped,
+    };
+    let key = input.merkle.verifying_key();
+    let verifying_key_status = match &key {
+        Ok(_) => CheckStatus::Passed,
+        Err(e) => CheckStatus::Failed(*e),
+    };
+    let verification = match key {
+        Ok(key) if proof_encoding.is_passed() && proof_a.error().is_none() => {
+            let public_inputs = withdraw_public_inputs(input.recipient, &input.args.nullifier_hash);
+            CheckStatus::from_result(verify_withdraw(&input.args.proof, &public_inputs, &key.key))
+        }
+        _ => CheckStatus::Skipped,
+    };
+    let nullifier_unspent = if input.nullifier_account_exists {
+        CheckStatus::Failed(ErrorCodes::NullifierAlreadySpent)
+    } else {
+        CheckStatus::Passed
+    };
+    PreflightReport {
+        merkle_state,
+        known_root,
+        signer_not_recipient,
+        proof_encoding,
+        proof_a,
+        verifying_key: verifying_key_status,
+        verification,
+        nullifier_unspent,
+    }
+}
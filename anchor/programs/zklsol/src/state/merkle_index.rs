@@ -0,0 +1,106 @@
+use crate::error::ErrorCodes;
+
+/// Largest supported tree depth (levels including the root).
+pub const MAX_MERKLE_DEPTH: u8 = 32;
+
+/// Position of a node addressed by its level (0 = leaves) and its offset within that level.
+/// The absolute index used for `MerkleNodeState` seeds numbers the leaves first,
+/// then every level above them, ending with the root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeIndex {
+    pub level: u8,
+    pub offset: u64,
+}
+
+impl NodeIndex {
+    pub fn new(level: u8, offset: u64) -> Self {
+        Self { level, offset }
+    }
+
+    pub fn leaf(offset: u64) -> Self {
+        Self::new(0, offset)
+    }
+
+    /// Height of a tree (number of levels above the leaves) for a given depth.
+    pub fn height_for_depth(depth: u8) -> Result<u8, ErrorCodes> {
+        if depth == 0 {
+            return Err(ErrorCodes::MerkleErrorInvalidDepth);
+        }
+        if depth > MAX_MERKLE_DEPTH {
+            return Err(ErrorCodes::MerkleErrorTooLarge);
+        }
+        Ok(depth - 1)
+    }
+
+    /// Number of nodes living on `level`: 2^(height - level)
+    pub fn level_width(level: u8, height: u8) -> Result<u64, ErrorCodes> {
+        let shift = height
+            .checked_sub(level)
+            .ok_or(ErrorCodes::InvalidNodeIndex)?;
+        1u64.checked_shl(shift as u32)
+            .ok_or(ErrorCodes::NumericalOverflow)
+    }
+
+    /// Absolute index of the first node on `level`: 2^(H+1) - 2^(H-L+1)
+    pub fn level_start(level: u8, height: u8) -> Result<u64, ErrorCodes> {
+        let total = 1u64
+            .checked_shl((height as u32) + 1)
+            .ok_or(ErrorCodes::NumericalOverflow)?;
+        let above = Self::level_width(level, height)?
+            .checked_mul(2)
+            .ok_or(ErrorCodes::NumericalOverflow)?;
+        total
+            .checked_sub(above)
+            .ok_or(ErrorCodes::NumericalOverflow)
+    }
+
+    /// Total number of nodes in the tree: 2^(H+1) - 1
+    pub fn total_nodes(height: u8) -> Result<u64, ErrorCodes> {
+        1u64.checked_shl((height as u32) + 1)
+            .and_then(|n| n.checked_sub(1))
+            .ok_or(ErrorCodes::NumericalOverflow)
+    }
+
+    pub fn absolute(&self, height: u8) -> Result<u64, ErrorCodes> {
+        if self.offset >= Self::level_width(self.level, height)? {
+            return Err(ErrorCodes::InvalidNodeIndex);
+        }
+        Self::level_start(self.level, height)?
+            .checked_add(self.offset)
+            .ok_or(ErrorCodes::NumericalOverflow)
+    }
+
+    pub fn from_absolute(index: u64, height: u8) -> Result<Self, ErrorCodes> {
+        for level in 0..=height {
+            let start = Self::level_start(level, height)?;
+            let width = Self::level_width(level, height)?;
+            let end = start
+                .checked_add(width)
+                .ok_or(ErrorCodes::NumericalOverflow)?;
+            if index < end {
+                return Ok(Self::new(level, index - start));
+            }
+        }
+        Err(ErrorCodes::InvalidNodeIndex)
+    }
+
+    pub fn is_root(&self, height: u8) -> bool {
+        self.level == height
+    }
+
+    pub fn is_left(&self) -> bool {
+        self.offset % 2 == 0
+    }
+
+    pub fn sibling(&self) -> Self {
+        Self::new(self.level, self.offset ^ 1)
+    }
+
+    /// Returns `None` for the root.
+    pub fn parent(&self, height: u8) -> Option<Self> {
+        if self.level >= height {
+            return None;
+        }
+        Some(Self::new(self.level + 1, self.offset / 2))
+    }
+}
@@ -1,41 +1,102 @@
+use crate::error::ErrorCodes;
+use crate::state::merkle_index::NodeIndex;
 use crate::state::merkle_node::MerkleNode;
 use anchor_lang::{AnchorDeserialize, AnchorSerialize};
 
-/// PathElement matching circom circuit
-/// The path contains each sibling node, their index and if they are left or right
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
-pub struct PathElement {
-    pub index: u64,
+/// A node an insert changed, the ancestor of the new leaf on `level` (0 = the leaf).
+#[derive(Debug, Clone, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct ChangedNode {
+    pub level: u8,
     pub node: MerkleNode,
 }
 
-impl PathElement {
+impl ChangedNode {
     pub const SIZE: usize =
-        // index
-        8 +
+        // level
+        1 +
         // node
         MerkleNode::SIZE;
-    pub fn new(index: u64, node: &MerkleNode) -> Self {
-        Self {
-            index,
-            node: node.clone(),
-        }
-    }
 }
 
+/// Nodes `MerkleTree::insert` changed, one per level from the leaf up to the root.
+/// Siblings are left out: a left one was recorded by the insert that changed it and
+/// a right one is still empty, a missing `MerkleNodeState` is `MerkleZeros::get(level)`.
 #[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct MerkleProof {
-    pub path: Vec<PathElement>,
+    pub nodes: Vec<ChangedNode>,
+}
+
+impl Default for MerkleProof {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MerkleProof {
-    pub const SIZE: usize = 4 + 2 * 20 * PathElement::SIZE;
+    /// Serialized size of the proof of an insert into a tree of `depth`.
+    pub fn size(depth: u64) -> usize {
+        4 + depth as usize * ChangedNode::SIZE
+    }
 
     pub fn new() -> Self {
-        Self { path: vec![] }
+        Self { nodes: vec![] }
+    }
+
+    pub fn push(&mut self, level: u8, node: &MerkleNode) {
+        self.nodes.push(ChangedNode {
+            level,
+            node: node.clone(),
+        });
+    }
+
+    /// The new root, as long as no node was removed from the proof yet.
+    pub fn root(&self) -> Option<&MerkleNode> {
+        self.nodes.last().map(|changed| &changed.node)
+    }
+
+    /// Absolute index of the ancestor of `leaf_index` on `level`.
+    pub fn node_index(leaf_index: u64, level: u8, height: u8) -> Result<u64, ErrorCodes> {
+        let offset = leaf_index
+            .checked_shr(level as u32)
+            .ok_or(ErrorCodes::InvalidNodeIndex)?;
+        NodeIndex::new(level, offset).absolute(height)
     }
+}
+
+/// Proof entry of the layout before `ChangedNode`: changed nodes and their siblings,
+/// addressed by absolute index.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyPathElement {
+    pub index: u64,
+    pub node: MerkleNode,
+}
+
+impl LegacyPathElement {
+    pub const SIZE: usize =
+        // index
+        8 +
+        // node
+        MerkleNode::SIZE;
+}
 
-    pub fn push(&mut self, sibling: PathElement) {
-        self.path.push(sibling);
+/// Proof of the layout before `ChangedNode`, room for 2 entries per level of a depth 20 tree.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyMerkleProof {
+    pub path: Vec<LegacyPathElement>,
+}
+
+impl LegacyMerkleProof {
+    pub const SIZE: usize = 4 + 2 * 20 * LegacyPathElement::SIZE;
+
+    /// The changed nodes of the insert of `leaf_index`, siblings dropped.
+    pub fn upgrade(&self, leaf_index: u64, height: u8) -> Result<MerkleProof, ErrorCodes> {
+        let mut proof = MerkleProof::new();
+        for element in &self.path {
+            let index = NodeIndex::from_absolute(element.index, height)?;
+            if Some(index.offset) == leaf_index.checked_shr(index.level as u32) {
+                proof.push(index.level, &element.node);
+            }
+        }
+        Ok(proof)
     }
 }
@@ -1,13 +1,19 @@
 use crate::error::ErrorCodes;
-use crate::state::merkle_proof::{MerkleProof, PathElement};
+use crate::state::merkle_index::NodeIndex;
+use crate::state::merkle_proof::{ChangedNode, LegacyMerkleProof, MerkleProof};
 use crate::state::merkle_tree::{FromBytesMerkleTree, MerkleTree};
+use crate::withdraw_verifier::{
+    deposit_verifying_key, verifying_key, ProofSystem, VerifyingKeyMetadata,
+};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_lang::solana_program::clock::Epoch;
+use anchor_lang::Discriminator;
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
 
 #[account]
+#[derive(Default, Debug, PartialEq)]
 pub struct MerkleState {
     pub bump: u8,
     pub depth: u64,
@@ -20,6 +26,15 @@ pub struct MerkleState {
     pub mint: Pubkey,
     pub roots: Vec<[u8; 32]>,
     pub filled_sub_trees: Vec<[u8; 32]>,
+    pub proof_system: ProofSystem,
+    /// `WithdrawVerifyingKey::hash` of the key the pool was created for.
+    pub verifying_key_hash: [u8; 32],
+    /// Name of the withdraw circuit, see `withdraw_verifier::circuit_id`.
+    pub circuit_id: [u8; 32],
+    /// Makes `deposit` take a `deposit_commitment` proof that the leaf is a note.
+    pub deposit_proof_required: bool,
+    /// Hash of the `deposit_commitment` key at `create_merkle`, zero if there was none.
+    pub deposit_verifying_key_hash: [u8; 32],
 }
 
 impl MerkleState {
@@ -35,29 +50,110 @@ impl MerkleState {
             // highest_pending_proof_index
             8 +
             // roots
-            30 * 32 + 4 +
+            32 * 32 + 4 +
             // depth
             8 +
+            // deposit_size
+            8 +
+            // number_of_deposits
+            8 +
             // current_root_index
             8 +
             // next_index
             8 +
+            // mint
+            32 +
             // filled_sub_trees
             32 * 32 + 4 +
+            // proof_system
+            1 +
+            // verifying_key_hash
+            32 +
+            // circuit_id
+            32 +
+            // deposit_proof_required
+            1 +
+            // deposit_verifying_key_hash
+            32 +
             // padding
             100;
 
-    pub fn to_merkle_tree(&self) -> MerkleTree {
+    /// A pool holding `tree` with every leaf's proof still pending, the state tests and
+    /// off-chain tooling start from. Other fields keep their defaults.
+    pub fn from_tree(mint: Pubkey, deposit_size: u64, tree: MerkleTree) -> Self {
+        let mut state = Self {
+            mint,
+            deposit_size,
+            number_of_deposits: tree.next_index,
+            highest_pending_proof_index: tree.next_index,
+            ..Default::default()
+        };
+        state.sync(tree);
+        state
+    }
+
+    pub fn to_merkle_tree(&self) -> std::result::Result<MerkleTree, ErrorCodes> {
+        let depth = u8::try_from(self.depth).map_err(|_| ErrorCodes::InvalidMerkleDepth)?;
         let bytes = FromBytesMerkleTree {
             roots: self.roots.to_vec(),
             current_root_index: self.current_root_index,
             filled_sub_trees: self.filled_sub_trees.to_vec(),
-            depth: self.depth as u8,
+            depth,
             next_index: self.next_index,
         };
         MerkleTree::deserialize(bytes)
     }
 
+    /// Records the key `withdraw` will use for this pool's depth and proof system.
+    pub fn pin_verifying_key(&mut self) -> std::result::Result<(), ErrorCodes> {
+        let key = verifying_key(self.depth, self.proof_system)?;
+        self.verifying_key_hash = key.key.hash();
+        self.circuit_id = key.circuit_id()?;
+        // A pool requiring deposit proofs is useless without the key to check them.
+        self.deposit_verifying_key_hash = match deposit_verifying_key(self.proof_system) {
+            Ok(key) => key.key.hash(),
+            Err(error) if self.deposit_proof_required => return Err(error),
+            Err(_) => [0u8; 32],
+        };
+        Ok(())
+    }
+
+    /// Pools created before keys were pinned decode with a zero hash and circuit id.
+    /// `create_merkle` never leaves a new pool like that, it fails without a key.
+    pub fn is_legacy_pin(&self) -> bool {
+        self.verifying_key_hash == [0u8; 32] && self.circuit_id == [0u8; 32]
+    }
+
+    /// The key to verify withdrawals with, refusing if the program's key for this
+    /// pool is no longer the one pinned at `create_merkle`. Legacy pools keep using
+    /// the program's key for their depth.
+    pub fn verifying_key(&self) -> std::result::Result<&'static VerifyingKeyMetadata, ErrorCodes> {
+        let key = verifying_key(self.depth, self.proof_system)?;
+        if self.is_legacy_pin() {
+            return Ok(key);
+        }
+        if key.key.hash() != self.verifying_key_hash || key.circuit_id()? != self.circuit_id {
+            return Err(ErrorCodes::VerifyingKeyMismatch);
+        }
+        Ok(key)
+    }
+
+    /// Same as `verifying_key` for `deposit_commitment` proofs.
+    pub fn deposit_verifying_key(
+        &self,
+    ) -> std::result::Result<&'static VerifyingKeyMetadata, ErrorCodes> {
+        let key = deposit_verifying_key(self.proof_system)?;
+        if key.key.hash() != self.deposit_verifying_key_hash {
+            return Err(ErrorCodes::VerifyingKeyMismatch);
+        }
+        Ok(key)
+    }
+
+    /// Root after the latest insert, `None` before the tree was initialized.
+    pub fn current_root(&self) -> Option<[u8; 32]> {
+        self.roots.get(self.current_root_index as usize).copied()
+    }
+
     pub fn sync(&mut self, m: MerkleTree) {
         self.depth = m.depth as u64;
         self.current_root_index = m.current_root_index;
@@ -78,40 +174,95 @@ pub struct MerklePendingProofState {
 
 impl MerklePendingProofState {
     pub const SEED: &'static str = "MerklePendingProof";
-    pub const SIZE: usize =
+
+    /// Space taken by pending proofs written before `ChangedNode`, which also counted
+    /// 64 bytes of "data" and "owner" the struct never had.
+    pub const LEGACY_SIZE: usize =
         // discriminator
         8 +
-    // bump
-    1 +
-    // index
-    8 +
-    // depth
-    8 +
-    // data
-    32 +
-    // owner
-    32 +
-    // mint
-    32 +
-    // proof path
-    MerkleProof::SIZE;
-
-    pub fn generate_map(&self, depth: u64, program_id: &Pubkey) -> HashMap<Pubkey, PathElement> {
-        let depth_binding = depth.to_le_bytes();
-        let mut map: HashMap<Pubkey, PathElement> = HashMap::new();
-        for p in &self.proof.path {
-            let index_binding = p.index.to_le_bytes();
-            let seeds = &[
-                MerkleNodeState::SEED.as_bytes(),
-                depth_binding.as_ref(),
-                index_binding.as_ref(),
-            ];
-            let (expected_pda, _bump) = Pubkey::find_program_address(seeds, program_id);
-            map.insert(expected_pda, p.clone());
+        // bump
+        1 +
+        // index
+        8 +
+        // depth
+        8 +
+        // data
+        32 +
+        // owner
+        32 +
+        // mint
+        32 +
+        // proof path
+        LegacyMerkleProof::SIZE;
+
+    /// Space for the pending proof of an insert into a pool of `depth`. Never
+    /// `LEGACY_SIZE`, which is how old pending proofs are told apart and sent
+    /// through `upgrade_pending_proof` before they can be dumped.
+    pub fn size(depth: u64) -> usize {
+        // discriminator
+        8 +
+        // bump
+        1 +
+        // index
+        8 +
+        // depth
+        8 +
+        // mint
+        32 +
+        // proof nodes
+        MerkleProof::size(depth)
+    }
+
+    /// Decodes a pending proof written in the `LEGACY_SIZE` layout.
+    pub fn from_legacy(data: &[u8]) -> std::result::Result<Self, ErrorCodes> {
+        if data.len() != Self::LEGACY_SIZE || !data.starts_with(&Self::discriminator()) {
+            return Err(ErrorCodes::NotLegacyPendingProof);
         }
+        let legacy = LegacyMerklePendingProofState::deserialize(&mut &data[8..])
+            .map_err(|_| ErrorCodes::NotLegacyPendingProof)?;
+        let depth = u8::try_from(legacy.depth).map_err(|_| ErrorCodes::InvalidMerkleDepth)?;
+        let proof = legacy
+            .proof
+            .upgrade(legacy.index, NodeIndex::height_for_depth(depth)?)?;
+        Ok(Self {
+            bump: legacy.bump,
+            depth: legacy.depth,
+            index: legacy.index,
+            mint: legacy.mint,
+            proof,
+        })
+    }
 
-        map
+    /// Absolute index of the changed node on `level`.
+    pub fn node_index(&self, level: u8) -> std::result::Result<u64, ErrorCodes> {
+        let depth = u8::try_from(self.depth).map_err(|_| ErrorCodes::InvalidMerkleDepth)?;
+        MerkleProof::node_index(self.index, level, NodeIndex::height_for_depth(depth)?)
     }
+
+    /// Changed nodes still to be written, by their `MerkleNodeState` address.
+    pub fn generate_map(
+        &self,
+        program_id: &Pubkey,
+    ) -> std::result::Result<HashMap<Pubkey, ChangedNode>, ErrorCodes> {
+        let mut map: HashMap<Pubkey, ChangedNode> = HashMap::new();
+        for changed in &self.proof.nodes {
+            let index = self.node_index(changed.level)?;
+            let (expected_pda, _bump) =
+                MerkleNodeState::find_address(self.depth, index, program_id)?;
+            map.insert(expected_pda, changed.clone());
+        }
+        Ok(map)
+    }
+}
+
+/// `MerklePendingProofState` as written before `ChangedNode`.
+#[derive(AnchorDeserialize)]
+struct LegacyMerklePendingProofState {
+    bump: u8,
+    depth: u64,
+    index: u64,
+    mint: Pubkey,
+    proof: LegacyMerkleProof,
 }
 
 #[account]
@@ -132,6 +283,25 @@ impl MerkleNodeState {
         8 +
         // data
         32;
+
+    /// Derives the node PDA, rejecting indices that don't exist in a tree of `depth`.
+    pub fn find_address(
+        depth: u64,
+        index: u64,
+        program_id: &Pubkey,
+    ) -> std::result::Result<(Pubkey, u8), ErrorCodes> {
+        let depth_u8 = u8::try_from(depth).map_err(|_| ErrorCodes::InvalidMerkleDepth)?;
+        let height = NodeIndex::height_for_depth(depth_u8)?;
+        NodeIndex::from_absolute(index, height)?;
+        let depth_binding = depth.to_le_bytes();
+        let index_binding = index.to_le_bytes();
+        let seeds = &[
+            Self::SEED.as_bytes(),
+            depth_binding.as_ref(),
+            index_binding.as_ref(),
+        ];
+        Ok(Pubkey::find_program_address(seeds, program_id))
+    }
 }
 
 #[account]
@@ -149,4 +319,82 @@ impl NullifierHash {
         1 +
         // nullifier_hash
         32;
+
+    /// The account `withdraw` creates, it existing means the nullifier was spent. Keyed
+    /// by depth, not by pool: every pool of one depth shares the nullifier set, whatever
+    /// its mint.
+    pub fn find_address(
+        depth: u64,
+        nullifier_hash: &[u8; 32],
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::SEED.as_bytes(),
+                depth.to_le_bytes().as_ref(),
+                nullifier_hash.as_slice(),
+            ],
+            program_id,
+        )
+    }
+}
+
+#[account]
+pub struct DepositedCommitment {
+    pub bump: u8,
+    pub merkle: Pubkey,
+    /// Leaf index the commitment was inserted at.
+    pub index: u64,
+}
+
+impl DepositedCommitment {
+    pub const SEED: &'static str = "DepositedCommitment";
+    pub const SIZE: usize =
+        // discriminator
+        8 +
+        // bump
+        1 +
+        // merkle
+        32 +
+        // index
+        8;
+
+    /// The account a commitment is recorded in by every instruction inserting a leaf.
+    /// A second note with the same commitment could never be spent, as both share one
+    /// nullifier.
+    pub fn find_address(
+        merkle: &Pubkey,
+        commitment: &[u8; 32],
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::SEED.as_bytes(),
+                merkle.as_ref(),
+                commitment.as_slice(),
+            ],
+            program_id,
+        )
+    }
+
+    /// Instructions create the account if needed, so a fresh one is all zeros.
+    pub fn is_recorded(&self) -> bool {
+        self.merkle != Pubkey::default()
+    }
+
+    /// Records the commitment as `merkle`'s leaf at `index`, unless it already is one.
+    pub fn record(
+        &mut self,
+        bump: u8,
+        merkle: Pubkey,
+        index: u64,
+    ) -> std::result::Result<(), ErrorCodes> {
+        if self.is_recorded() {
+            return Err(ErrorCodes::DuplicateCommitment);
+        }
+        self.bump = bump;
+        self.merkle = merkle;
+        self.index = index;
+        Ok(())
+    }
 }
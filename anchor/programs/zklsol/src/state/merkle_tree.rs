@@ -1,6 +1,7 @@
 use crate::error::ErrorCodes;
+use crate::state::merkle_index::NodeIndex;
 use crate::state::merkle_node::MerkleNode;
-use crate::state::merkle_proof::{MerkleProof, PathElement};
+use crate::state::merkle_proof::MerkleProof;
 use crate::state::merkle_zeros::MerkleZeros;
 use crate::utils::number_array_to_bigint;
 use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
@@ -31,40 +32,27 @@ impl MerkleTree {
         self.roots.iter().any(|i| i.1.hash == *root)
     }
 
-    pub fn parent_index(i: usize, height: usize) -> Option<usize> {
-        // Total nodes in the tree: 2^(H+1) - 1
-        let total_nodes = 2usize.pow((height + 1usize) as u32) - 1;
-        // Root index: 2^(H+1) - 2
-        let root_index = total_nodes - 1;
+    pub fn height(&self) -> Result<u8, ErrorCodes> {
+        NodeIndex::height_for_depth(self.depth)
+    }
 
-        // If i is the root, it has no parent
-        if i == root_index {
-            return None;
-        }
-        // Check if i is a valid index
-        if i >= total_nodes {
-            panic!(
-                "Index {} exceeds total nodes {} for height {}",
-                i, total_nodes, height
-            );
-        }
+    /// Index of the last leaf a tree of `depth` can hold: 2^(depth - 1) - 1
+    pub fn last_leaf_index(depth: u8) -> Result<u64, ErrorCodes> {
+        let height = NodeIndex::height_for_depth(depth)?;
+        NodeIndex::level_width(0, height)?
+            .checked_sub(1)
+            .ok_or(ErrorCodes::NumericalOverflow)
+    }
 
-        // Calculate the level L of node i
-        // L = floor(log2(i + 2^H)) - H
-        let leaf_start = 2usize.pow(height as u32); // 2^H, start of leaf level
-        let level = (usize::ilog2(i + leaf_start) as usize) - height;
-        // Calculate parent index
-        // p = (2^(H+1) - 2^(H-L)) + floor((i - (2^(H+1) - 2^(H-L+1))) / 2)
-        let nodes_up_to_next_level =
-            2usize.pow((height + 1) as u32) - 2usize.pow((height - level) as u32);
-        let nodes_up_to_current_level =
-            2usize.pow((height + 1) as u32) - 2usize.pow((height - level + 1) as u32);
-        let offset = i - nodes_up_to_current_level;
-        let parent = nodes_up_to_next_level + (offset / 2);
-        Some(parent)
+    /// Absolute index of the parent of node `i`, `None` if `i` is the root.
+    pub fn parent_index(i: u64, height: u8) -> Result<Option<u64>, ErrorCodes> {
+        match NodeIndex::from_absolute(i, height)?.parent(height) {
+            Some(parent) => Ok(Some(parent.absolute(height)?)),
+            None => Ok(None),
+        }
     }
 
-    pub fn deserialize(bytes: FromBytesMerkleTree) -> Self {
+    pub fn deserialize(bytes: FromBytesMerkleTree) -> Result<Self, ErrorCodes> {
         let mut roots: BTreeMap<u8, MerkleNode> = BTreeMap::new();
         for r in bytes.roots.iter().enumerate() {
             roots.insert(r.0 as u8, MerkleNode::deserialize(r.1));
@@ -73,24 +61,21 @@ impl MerkleTree {
         for r in bytes.filled_sub_trees.iter().enumerate() {
             filled_sub_trees.insert(r.0 as u8, MerkleNode::deserialize(r.1));
         }
-        let number_of_leaves = 2u64.pow((bytes.depth - 1) as u32) - 1;
-        Self {
+        let number_of_leaves = Self::last_leaf_index(bytes.depth)?;
+        Ok(Self {
             number_of_leaves,
             roots,
             filled_sub_trees,
             current_root_index: bytes.current_root_index,
             next_index: bytes.next_index,
             depth: bytes.depth,
-        }
+        })
     }
 
     /// Since the tree is of fixed depth, and initially all leafs are zero.
     /// We can populate the filled sub-trees with zeros from pre-calculated values
     pub fn new(depth: u8, zeros: &MerkleZeros) -> Result<Self, ErrorCodes> {
-        let number_of_leaves = 2u64.pow((depth - 1) as u32) - 1;
-        if depth > 32 {
-            return Err(ErrorCodes::MerkleErrorTooLarge);
-        }
+        let number_of_leaves = Self::last_leaf_index(depth)?;
         let mut filled_sub_trees: BTreeMap<u8, MerkleNode> = BTreeMap::new();
         let mut roots: BTreeMap<u8, MerkleNode> = BTreeMap::new();
         for i in 0..depth {
@@ -122,74 +107,67 @@ impl MerkleTree {
         if self.next_index > self.number_of_leaves {
             return Err(ErrorCodes::MerkleErrorFull);
         }
-        let mut current_index = self.next_index;
-        let mut absolute_current_index = self.next_index;
+        let height = self.height()?;
+        let mut node_index = NodeIndex::leaf(self.next_index);
         let mut current_level_node = MerkleNode::deserialize(data);
         let mut left: MerkleNode;
         let mut right: MerkleNode;
         let mut proof: MerkleProof = MerkleProof::new();
 
-        for i in 0..self.depth - 1 {
-            if current_index % 2 == 0 {
+        for i in 0..height {
+            proof.push(i, &current_level_node);
+            if node_index.is_left() {
                 left = current_level_node.clone();
                 right = MerkleNode::deserialize(&zeros.get(i));
                 self.filled_sub_trees.insert(i, current_level_node.clone());
             } else {
-                left = self.filled_sub_trees.get(&i).unwrap().clone();
+                left = self
+                    .filled_sub_trees
+                    .get(&i)
+                    .ok_or(ErrorCodes::CantFindMerkleNode)?
+                    .clone();
                 right = current_level_node.clone();
             }
             current_level_node = MerkleNode::from_children(&left, &right);
-            if absolute_current_index % 2 == 0 {
-                proof.push(PathElement::new(absolute_current_index, &left));
-                proof.push(PathElement::new(absolute_current_index + 1, &right));
-            } else {
-                proof.push(PathElement::new(absolute_current_index - 1, &left));
-                proof.push(PathElement::new(absolute_current_index, &right));
-            }
-
-            if let Some(parent) =
-                Self::parent_index(absolute_current_index as usize, (self.depth - 1) as usize)
-            {
-                absolute_current_index = parent as u64;
-            }
-            current_index = current_index / 2;
+            node_index = node_index
+                .parent(height)
+                .ok_or(ErrorCodes::InvalidNodeIndex)?;
         }
-        proof.push(PathElement::new(
-            absolute_current_index,
-            &current_level_node,
-        ));
+        proof.push(height, &current_level_node);
         self.current_root_index = (self.current_root_index + 1) % ROOT_HISTORY_SIZE;
         self.roots
             .insert(self.current_root_index, current_level_node);
-        self.next_index += 1;
+        self.next_index = self
+            .next_index
+            .checked_add(1)
+            .ok_or(ErrorCodes::NumericalOverflow)?;
         Ok(proof)
     }
 
-    pub fn generate_proof_path(&self, leaf_index: u64) -> Vec<Vec<u64>> {
-        let total_nodes = 2u64.pow(self.depth as u32) - 2;
+    /// For every level below the root returns `[left, right, is_right]` absolute indices,
+    /// followed by `[root]`.
+    pub fn generate_proof_path(&self, leaf_index: u64) -> Result<Vec<Vec<u64>>, ErrorCodes> {
+        let height = self.height()?;
         let mut path: Vec<Vec<u64>> = Vec::with_capacity(self.depth as usize);
-        let mut absolute_current_index = leaf_index;
-        for _ in 0..self.depth {
-            let is_left = absolute_current_index % 2 == 0;
-            let sibling_index = if is_left {
-                absolute_current_index + 1
-            } else {
-                absolute_current_index - 1
+        let mut node_index = NodeIndex::leaf(leaf_index);
+        loop {
+            let absolute_current_index = node_index.absolute(height)?;
+            let parent = match node_index.parent(height) {
+                Some(parent) => parent,
+                None => {
+                    path.push(vec![absolute_current_index]);
+                    break;
+                }
             };
-            if absolute_current_index >= total_nodes {
-                path.push(vec![absolute_current_index]);
-            } else if absolute_current_index % 2 == 0 {
+            let sibling_index = node_index.sibling().absolute(height)?;
+            if node_index.is_left() {
                 path.push(vec![absolute_current_index, sibling_index, 0]);
             } else {
                 path.push(vec![sibling_index, absolute_current_index, 1]);
             }
-            if let Some(parent) =
-                Self::parent_index(absolute_current_index as usize, (self.depth - 1) as usize)
-            {
-                absolute_current_index = parent as u64;
-            }
+            node_index = parent;
         }
-        path
+        Ok(path)
     }
 
     pub fn roots_as_vec(&self) -> Vec<[u8; 32]> {
@@ -215,7 +193,9 @@ impl MerkleTree {
 
 #[cfg(test)]
 mod tests {
+    use crate::state::merkle_index::NodeIndex;
     use crate::state::merkle_node::MerkleNode;
+    use crate::state::merkle_proof::MerkleProof;
     use crate::state::merkle_tree::MerkleTree;
     use crate::state::merkle_zeros::MerkleZeros;
     use sha2::{Digest, Sha256};
@@ -228,11 +208,11 @@ mod tests {
         for i in 0..tree.number_of_leaves {
             let mut hasher = Sha256::new();
             let data = i.to_le_bytes();
-            hasher.update(&data);
+            hasher.update(data);
             let data = <[u8; 32]>::from(hasher.finalize());
             let _proof1 = tree.insert(&data, &zeros).unwrap();
             println!("proof1 = {:?}", _proof1);
-            let _proof2 = tree.generate_proof_path(i);
+            let _proof2 = tree.generate_proof_path(i).unwrap();
         }
     }
 
@@ -249,10 +229,42 @@ mod tests {
         assert_eq!(root.hash, v[0]);
     }
 
+    #[test]
+    fn depth_32_indexing_test() {
+        let depth = 32;
+        let zeros = MerkleZeros::new(depth, 0);
+        let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+        let height = tree.height().unwrap();
+        let root_index = NodeIndex::total_nodes(height).unwrap() - 1;
+        let proof = tree.insert(&[1u8; 32], &zeros).unwrap();
+        assert_eq!(proof.nodes.len(), depth as usize);
+        assert_eq!(
+            MerkleProof::node_index(0, proof.nodes.last().unwrap().level, height).unwrap(),
+            root_index
+        );
+        assert_eq!(MerkleTree::parent_index(root_index, height).unwrap(), None);
+        assert!(MerkleTree::parent_index(root_index + 1, height).is_err());
+
+        let last_leaf = tree.number_of_leaves;
+        let path = tree.generate_proof_path(last_leaf).unwrap();
+        assert_eq!(path.len(), depth as usize);
+        assert_eq!(path[0], vec![last_leaf - 1, last_leaf, 1]);
+        assert_eq!(path.last().unwrap(), &vec![root_index]);
+        assert!(tree.generate_proof_path(last_leaf + 1).is_err());
+
+        tree.next_index = last_leaf;
+        let proof = tree.insert(&[2u8; 32], &zeros).unwrap();
+        assert_eq!(
+            MerkleProof::node_index(last_leaf, proof.nodes.last().unwrap().level, height).unwrap(),
+            root_index
+        );
+        assert!(tree.insert(&[3u8; 32], &zeros).is_err());
+    }
+
     #[test]
     fn cache_generation_test() {
         let data = "ZKL$SOL".as_bytes();
-        let leaf = MerkleNode::hash(&data);
+        let leaf = MerkleNode::hash(data);
         let depth = 5;
         let mut levels: HashMap<u64, MerkleNode> = HashMap::new();
         let mut current_node = leaf.clone();
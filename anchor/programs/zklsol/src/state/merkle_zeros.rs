@@ -21,7 +21,7 @@ impl MerkleZeros {
 
     pub fn new(depth: u8, bump: u8) -> Self {
         let data = "ZKL$SOL".as_bytes();
-        let leaf = MerkleNode::hash(&data);
+        let leaf = MerkleNode::hash(data);
         let mut zeros: Vec<[u8; 32]> = Vec::new();
         let mut current_node = leaf.clone();
         for i in 0..depth {
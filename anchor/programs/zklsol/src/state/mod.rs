@@ -1,3 +1,4 @@
+pub mod merkle_index;
 pub mod merkle_node;
 pub mod merkle_proof;
 pub mod merkle_state;
@@ -52,8 +52,11 @@ impl MerkleNodeWasm {
     #[wasm_bindgen]
     pub fn generate_proof_path(depth: u8, leaf_index: u64) -> Result<JsValue, JsValue> {
         let zeros = MerkleZeros::new(depth, 0);
-        let merkle = MerkleTree::new(depth, &zeros).unwrap();
-        let proof_path = merkle.generate_proof_path(leaf_index);
+        let merkle =
+            MerkleTree::new(depth, &zeros).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let proof_path = merkle
+            .generate_proof_path(leaf_index)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
         serde_wasm_bindgen::to_value(&proof_path).map_err(|e| JsValue::from_str(&e.to_string()))
     }
 }
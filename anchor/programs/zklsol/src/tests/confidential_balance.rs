@@ -0,0 +1,198 @@
+use crate::confidential_balance::{
+    check_pool_mint, configure_pool_instruction, pool_payout_instructions,
+    CONFIDENTIAL_TRANSFER_EXTENSION, DECRYPTABLE_BALANCE_SIZE, POOL_ELGAMAL_PUBKEY,
+    POOL_MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER,
+};
+use crate::error::ErrorCodes;
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::confidential_transfer::ciphertext_extraction::transfer_amount_source_ciphertext;
+use anchor_spl::token_2022::spl_token_2022::extension::confidential_transfer::{
+    ConfidentialTransferAccount, ConfidentialTransferMint,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{ExtensionType, StateWithExtensionsMut};
+use anchor_spl::token_2022::spl_token_2022::state::Mint;
+use solana_zk_token_sdk::encryption::elgamal::{
+    ElGamalCiphertext, ElGamalKeypair, ElGamalPubkey, ElGamalSecretKey,
+};
+use solana_zk_token_sdk::instruction::{TransferData, ZkProofData};
+use solana_zk_token_sdk::zk_token_elgamal::{ops, pod};
+
+fn pool_keypair() -> ElGamalKeypair {
+    let mut one = [0u8; 32];
+    one[0] = 1;
+    let secret = ElGamalSecretKey::from_bytes(&one).unwrap();
+    ElGamalKeypair::new_for_tests(ElGamalPubkey::new(&secret), secret)
+}
+
+fn mint(extensions: &[ExtensionType], auto_approve: bool) -> Vec<u8> {
+    let mut data = vec![0u8; ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap()];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    for extension in extensions {
+        match extension {
+            ExtensionType::ConfidentialTransferMint => {
+                state
+                    .init_extension::<ConfidentialTransferMint>(true)
+                    .unwrap()
+                    .auto_approve_new_accounts = auto_approve.into();
+            }
+            ExtensionType::TransferFeeConfig => {
+                state.init_extension::<TransferFeeConfig>(true).unwrap();
+            }
+            _ => unreachable!(),
+        }
+    }
+    state.base = Mint {
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    data
+}
+
+#[test]
+fn pool_elgamal_pubkey_test() {
+    assert_eq!(pool_keypair().pubkey().to_bytes(), POOL_ELGAMAL_PUBKEY);
+}
+
+#[test]
+fn check_pool_mint_test() {
+    let confidential = [ExtensionType::ConfidentialTransferMint];
+    let token_2022 = spl_token_2022::ID;
+    check_pool_mint(&token_2022, &mint(&confidential, true)).unwrap();
+    check_pool_mint(&spl_token::ID, &[]).unwrap();
+
+    // Locked: nothing could ever pay the pool out, or the pool's account couldn't send.
+    for data in [mint(&[], true), mint(&confidential, false)] {
+        assert!(matches!(
+            check_pool_mint(&token_2022, &data),
+            Err(ErrorCodes::UnsupportedMint)
+        ));
+    }
+    // Insolvent: the pool would receive less than `deposit_size`.
+    let with_fee = mint(
+        &[
+            ExtensionType::ConfidentialTransferMint,
+            ExtensionType::TransferFeeConfig,
+        ],
+        true,
+    );
+    assert!(matches!(
+        check_pool_mint(&token_2022, &with_fee),
+        Err(ErrorCodes::UnsupportedMint)
+    ));
+}
+
+#[test]
+fn pool_payout_test() {
+    // Token-2022's ciphertext arithmetic on a freshly configured pool account, with a
+    // payout above 16 bits so both halves of the pending balance are used.
+    let deposit_size = 1_000_000u64;
+    let zero = pod::ElGamalCiphertext::default();
+    let pending_lo = ops::add_to(&zero, deposit_size & u16::MAX as u64).unwrap();
+    let pending_hi = ops::add_to(&zero, deposit_size >> 16).unwrap();
+    let available = ops::add_with_lo_hi(&zero, &pending_lo, &pending_hi).unwrap();
+
+    // Anyone can prove the transfer, the pool's secret key is public.
+    let pool = pool_keypair();
+    let recipient = ElGamalKeypair::new_rand();
+    let transfer = TransferData::new(
+        deposit_size,
+        (deposit_size, &available.try_into().unwrap()),
+        &pool,
+        (recipient.pubkey(), &ElGamalPubkey::default()),
+    )
+    .unwrap();
+    transfer.verify_proof().unwrap();
+
+    // What Token-2022 checks before debiting the source, it ends up empty again.
+    let context = transfer.context_data();
+    let remaining = ops::subtract_with_lo_hi(
+        &available,
+        &transfer_amount_source_ciphertext(&context.ciphertext_lo),
+        &transfer_amount_source_ciphertext(&context.ciphertext_hi),
+    )
+    .unwrap();
+    assert_eq!(remaining, context.new_source_ciphertext);
+    let remaining: ElGamalCiphertext = remaining.try_into().unwrap();
+    assert_eq!(remaining.decrypt_u32(pool.secret()), Some(0));
+
+    // The recipient's half of the grouped ciphertexts: commitment and destination handle.
+    let received = |ciphertext: &pod::TransferAmountCiphertext| {
+        let bytes = bytemuck::bytes_of(ciphertext);
+        let mut destination = [0u8; 64];
+        destination[..32].copy_from_slice(&bytes[..32]);
+        destination[32..].copy_from_slice(&bytes[64..96]);
+        let destination: ElGamalCiphertext =
+            pod::ElGamalCiphertext(destination).try_into().unwrap();
+        destination.decrypt_u32(recipient.secret()).unwrap()
+    };
+    assert_eq!(
+        received(&context.ciphertext_lo) + (received(&context.ciphertext_hi) << 16),
+        deposit_size
+    );
+}
+
+#[test]
+fn pool_instructions_test() {
+    let (account, mint, recipient_account, proof_context, merkle) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let balance = [9u8; DECRYPTABLE_BALANCE_SIZE];
+    let configure = configure_pool_instruction(&account, &mint, &proof_context, &merkle, &balance);
+    assert_eq!(configure.data[..2], [CONFIDENTIAL_TRANSFER_EXTENSION, 2]);
+    assert_eq!(configure.data[2..38], balance);
+    assert_eq!(
+        configure.data[38..46],
+        POOL_MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER.to_le_bytes()
+    );
+    assert_eq!(configure.data[46..], [0]);
+
+    let pool = ConfidentialTransferAccount {
+        pending_balance_credit_counter: 2.into(),
+        ..Default::default()
+    };
+    let [deposit, apply, transfer] = pool_payout_instructions(
+        &account,
+        &mint,
+        &recipient_account,
+        &proof_context,
+        &merkle,
+        &pool,
+        1_000,
+        6,
+        &balance,
+    )
+    .unwrap();
+    assert_eq!(deposit.data[..2], [CONFIDENTIAL_TRANSFER_EXTENSION, 5]);
+    assert_eq!(deposit.data[2..10], 1_000u64.to_le_bytes());
+    assert_eq!(apply.data[..2], [CONFIDENTIAL_TRANSFER_EXTENSION, 8]);
+    // Counts the payout's own deposit.
+    assert_eq!(apply.data[2..10], 3u64.to_le_bytes());
+    assert_eq!(transfer.data[..2], [CONFIDENTIAL_TRANSFER_EXTENSION, 7]);
+    assert_eq!(transfer.data[2..38], balance);
+    let keys: Vec<_> = transfer.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        keys,
+        [account, mint, recipient_account, proof_context, merkle]
+    );
+
+    // Only the pool signs, the recipient's wallet isn't part of the transaction.
+    for instruction in [configure, deposit, apply, transfer] {
+        let signers: Vec<_> = instruction
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| meta.pubkey)
+            .collect();
+        assert_eq!(signers, [merkle]);
+    }
+}
@@ -0,0 +1,40 @@
+use super::merkle_state_roundtrip::random_state;
+use crate::error::ErrorCodes;
+use crate::withdraw_verifier::{deposit_public_inputs, ProofSystem};
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn deposit_public_inputs_test() {
+    assert_eq!(deposit_public_inputs(&[5u8; 32]).unwrap(), [[5u8; 32]]);
+    let unreduced: [u8; 32] = Fr::MODULUS.to_bytes_be().try_into().unwrap();
+    assert!(matches!(
+        deposit_public_inputs(&unreduced),
+        Err(ErrorCodes::ValueCantBePoseidonHashed)
+    ));
+}
+
+#[test]
+fn deposit_proof_required_needs_key_test() {
+    let mut rng = StdRng::seed_from_u64(47);
+    let mut state = random_state(&mut rng);
+    state.depth = 3;
+    state.proof_system = ProofSystem::Groth16;
+
+    // No `deposit_commitment` key is compiled in yet.
+    state.deposit_proof_required = true;
+    assert!(matches!(
+        state.pin_verifying_key(),
+        Err(ErrorCodes::VerifyingKeyNotFound)
+    ));
+
+    state.deposit_proof_required = false;
+    state.pin_verifying_key().unwrap();
+    assert_eq!(state.deposit_verifying_key_hash, [0u8; 32]);
+    assert!(matches!(
+        state.deposit_verifying_key(),
+        Err(ErrorCodes::VerifyingKeyNotFound)
+    ));
+}
@@ -0,0 +1,72 @@
+use crate::error::ErrorCodes;
+use crate::state::merkle_state::DepositedCommitment;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+
+#[test]
+fn deposited_commitment_address_test() {
+    let program_id = crate::ID;
+    let merkle = Pubkey::new_unique();
+    let (address, _) = DepositedCommitment::find_address(&merkle, &[1; 32], &program_id);
+    assert_eq!(
+        address,
+        DepositedCommitment::find_address(&merkle, &[1; 32], &program_id).0
+    );
+    assert_ne!(
+        address,
+        DepositedCommitment::find_address(&merkle, &[2; 32], &program_id).0
+    );
+    // The same commitment may sit in two different pools.
+    assert_ne!(
+        address,
+        DepositedCommitment::find_address(&Pubkey::new_unique(), &[1; 32], &program_id).0
+    );
+}
+
+#[test]
+fn deposited_commitment_recorded_test() {
+    let fresh = DepositedCommitment {
+        bump: 0,
+        merkle: Pubkey::default(),
+        index: 0,
+    };
+    assert!(!fresh.is_recorded());
+    let record = DepositedCommitment {
+        bump: 255,
+        merkle: Pubkey::new_unique(),
+        index: 0,
+    };
+    assert!(record.is_recorded());
+
+    let mut data = Vec::new();
+    record.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), DepositedCommitment::SIZE);
+    let decoded = DepositedCommitment::try_deserialize(&mut data.as_slice()).unwrap();
+    assert!(decoded.is_recorded());
+    assert_eq!(decoded.index, record.index);
+}
+
+#[test]
+fn deposited_commitment_record_twice_test() {
+    let merkle = Pubkey::new_unique();
+    let mut account = DepositedCommitment {
+        bump: 0,
+        merkle: Pubkey::default(),
+        index: 0,
+    };
+    account.record(254, merkle, 7).unwrap();
+    assert_eq!(
+        (account.bump, account.merkle, account.index),
+        (254, merkle, 7)
+    );
+
+    // A second deposit of the commitment finds the account recorded and changes nothing.
+    assert!(matches!(
+        account.record(253, merkle, 8),
+        Err(ErrorCodes::DuplicateCommitment)
+    ));
+    assert_eq!(
+        (account.bump, account.merkle, account.index),
+        (254, merkle, 7)
+    );
+}
@@ -0,0 +1,104 @@
+use super::plonk_verifier::{g1, g2, random_fr};
+use crate::error::ErrorCodes;
+use crate::plonk_verifier::{fr_from_be, fr_to_be};
+use crate::withdraw_verifier::{
+    deposit_public_inputs, verify_withdraw, withdraw_public_inputs, ProofEncoding,
+    WithdrawVerifyingKey, PROOF_SIZE,
+};
+use anchor_lang::prelude::Pubkey;
+use ark_bn254::Fr;
+use ark_ff::Field;
+use groth16_solana::groth16::Groth16Verifyingkey;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Groth16 key with known trapdoors for a circuit with `N` public inputs,
+/// so proofs for any public inputs can be made.
+pub(super) struct Setup<const N: usize> {
+    pub key: &'static Groth16Verifyingkey<'static>,
+    alpha: Fr,
+    beta: Fr,
+    gamma: Fr,
+    delta: Fr,
+    ic: Vec<Fr>,
+}
+
+impl<const N: usize> Setup<N> {
+    pub fn new(rng: &mut StdRng) -> Self {
+        let [alpha, beta, gamma, delta] = [(); 4].map(|_| random_fr(rng));
+        let ic: Vec<Fr> = (0..=N).map(|_| random_fr(rng)).collect();
+        let key = Box::leak(Box::new(Groth16Verifyingkey {
+            nr_pubinputs: N + 1,
+            vk_alpha_g1: g1(&alpha),
+            vk_beta_g2: g2(&beta),
+            vk_gamme_g2: g2(&gamma),
+            vk_delta_g2: g2(&delta),
+            vk_ic: Box::leak(ic.iter().map(g1).collect::<Vec<_>>().into_boxed_slice()),
+        }));
+        Setup {
+            key,
+            alpha,
+            beta,
+            gamma,
+            delta,
+            ic,
+        }
+    }
+
+    /// Picks a and b at random and solves the verification equation for c.
+    pub fn prove(&self, rng: &mut StdRng, inputs: &[[u8; 32]; N]) -> [u8; PROOF_SIZE] {
+        let vk_x = inputs
+            .iter()
+            .zip(&self.ic[1..])
+            .fold(self.ic[0], |acc, (input, k)| {
+                acc + *k * fr_from_be(input).unwrap()
+            });
+        let (a, b) = (random_fr(rng), random_fr(rng));
+        let c =
+            (a * b - self.alpha * self.beta - vk_x * self.gamma) * self.delta.inverse().unwrap();
+        [&g1(&a)[..], &g2(&b)[..], &g1(&c)[..]]
+            .concat()
+            .try_into()
+            .unwrap()
+    }
+
+    /// Random public inputs and a proof for them.
+    pub fn prove_random(&self, rng: &mut StdRng) -> ([u8; PROOF_SIZE], [[u8; 32]; N]) {
+        let inputs = [(); N].map(|_| fr_to_be(&random_fr(rng)));
+        (self.prove(rng, &inputs), inputs)
+    }
+}
+
+/// A proof made for `inputs` verifies, the same proof for `other` doesn't.
+fn check_binds_inputs<const N: usize>(
+    rng: &mut StdRng,
+    inputs: [[u8; 32]; N],
+    other: [[u8; 32]; N],
+) {
+    let setup = Setup::<N>::new(rng);
+    let key = WithdrawVerifyingKey::Groth16(setup.key);
+    let proof = setup.prove(rng, &inputs);
+    verify_withdraw(&ProofEncoding::Uncompressed(proof), &inputs, &key).unwrap();
+    verify_withdraw(&ProofEncoding::compress(&proof).unwrap(), &inputs, &key).unwrap();
+    assert!(matches!(
+        verify_withdraw(&ProofEncoding::Uncompressed(proof), &other, &key),
+        Err(ErrorCodes::Groth16VerifyError)
+    ));
+}
+
+#[test]
+fn forged_proof_binds_public_inputs_test() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let field_element = |rng: &mut StdRng| fr_to_be(&random_fr(rng));
+    let withdraw = |rng: &mut StdRng| {
+        let recipient = Pubkey::new_from_array(field_element(rng));
+        withdraw_public_inputs(&recipient, &field_element(rng))
+    };
+    let (inputs, other) = (withdraw(&mut rng), withdraw(&mut rng));
+    check_binds_inputs(&mut rng, inputs, other);
+
+    // A proof for one note can't vouch for a leaf nobody knows the preimage of.
+    let deposit = |rng: &mut StdRng| deposit_public_inputs(&field_element(rng)).unwrap();
+    let (inputs, other) = (deposit(&mut rng), deposit(&mut rng));
+    check_binds_inputs(&mut rng, inputs, other);
+}
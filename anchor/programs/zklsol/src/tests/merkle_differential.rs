@@ -0,0 +1,157 @@
+use crate::error::ErrorCodes;
+use crate::state::merkle_proof::MerkleProof;
+use crate::state::merkle_tree::MerkleTree;
+use crate::state::merkle_zeros::MerkleZeros;
+use crate::tests::merkle_reference::ReferenceTree;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SEEDS: [u64; 4] = [1, 7, 42, 1337];
+const MAX_DEPTH: u8 = 6;
+
+fn random_leaf(rng: &mut StdRng) -> [u8; 32] {
+    let mut leaf = [0u8; 32];
+    rng.fill(&mut leaf);
+    leaf
+}
+
+#[test]
+fn insert_matches_reference_test() {
+    for seed in SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let depth = rng.random_range(1..=MAX_DEPTH);
+        let zeros = MerkleZeros::new(depth, 0);
+        let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+        let mut reference = ReferenceTree::new(depth, &zeros);
+        assert_eq!(tree.roots_as_vec(), vec![reference.root().hash]);
+
+        let leaves = rng.random_range(1..=reference.capacity());
+        for i in 0..leaves {
+            let leaf = random_leaf(&mut rng);
+            let proof = tree.insert(&leaf, &zeros).unwrap();
+            reference.set_leaf(i, &leaf);
+
+            let height = tree.height().unwrap();
+            let actual: Vec<(u64, [u8; 32])> = proof
+                .nodes
+                .iter()
+                .map(|p| {
+                    let index = MerkleProof::node_index(i, p.level, height).unwrap();
+                    (index, p.node.hash)
+                })
+                .collect();
+            assert_eq!(
+                actual,
+                reference.changed_nodes(i),
+                "seed {} depth {} leaf {}",
+                seed,
+                depth,
+                i
+            );
+            assert!(tree.known_root(&reference.root().hash));
+            assert_eq!(tree.next_index, i + 1);
+        }
+    }
+}
+
+#[test]
+fn proof_path_matches_reference_test() {
+    for depth in 1..=MAX_DEPTH {
+        let zeros = MerkleZeros::new(depth, 0);
+        let tree = MerkleTree::new(depth, &zeros).unwrap();
+        let reference = ReferenceTree::new(depth, &zeros);
+        for leaf_index in 0..reference.capacity() {
+            let path = tree.generate_proof_path(leaf_index).unwrap();
+            let expected = reference.proof(leaf_index);
+            assert_eq!(path.len(), depth as usize);
+            for (level, entry) in path.iter().enumerate().take(path.len() - 1) {
+                let left = expected[2 * level].0;
+                let right = expected[2 * level + 1].0;
+                let is_right = (leaf_index >> level) % 2;
+                assert_eq!(entry, &vec![left, right, is_right]);
+            }
+            assert_eq!(path.last().unwrap(), &vec![expected.last().unwrap().0]);
+        }
+        assert!(matches!(
+            tree.generate_proof_path(reference.capacity()),
+            Err(ErrorCodes::InvalidNodeIndex)
+        ));
+    }
+}
+
+#[test]
+fn reference_path_reconstructs_root_test() {
+    let mut rng = StdRng::seed_from_u64(SEEDS[0]);
+    let depth = 5;
+    let zeros = MerkleZeros::new(depth, 0);
+    let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+    let mut reference = ReferenceTree::new(depth, &zeros);
+    let mut leaves = Vec::new();
+    for i in 0..reference.capacity() {
+        let leaf = random_leaf(&mut rng);
+        tree.insert(&leaf, &zeros).unwrap();
+        reference.set_leaf(i, &leaf);
+        leaves.push(leaf);
+    }
+    let root = reference.root().hash;
+    for (i, leaf) in leaves.iter().enumerate() {
+        let (elements, indices) = reference.path(i as u64);
+        assert_eq!(
+            ReferenceTree::root_from_path(leaf, &elements, &indices),
+            root
+        );
+    }
+    assert!(tree.known_root(&root));
+}
+
+#[test]
+fn full_tree_rejects_insert_test() {
+    for depth in 1..=4 {
+        let zeros = MerkleZeros::new(depth, 0);
+        let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+        let capacity = 1u64 << (depth - 1);
+        for i in 0..capacity {
+            tree.insert(&[i as u8; 32], &zeros).unwrap();
+        }
+        let roots_before = tree.roots_as_vec();
+        assert!(matches!(
+            tree.insert(&[0xff; 32], &zeros),
+            Err(ErrorCodes::MerkleErrorFull)
+        ));
+        assert_eq!(tree.next_index, capacity);
+        assert_eq!(tree.roots_as_vec(), roots_before);
+    }
+}
+
+#[test]
+fn root_history_evicts_oldest_test() {
+    let mut rng = StdRng::seed_from_u64(SEEDS[1]);
+    let depth = 7;
+    let zeros = MerkleZeros::new(depth, 0);
+    let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+    let mut reference = ReferenceTree::new(depth, &zeros);
+    let mut history = vec![reference.root().hash];
+    for i in 0..40 {
+        let leaf = random_leaf(&mut rng);
+        tree.insert(&leaf, &zeros).unwrap();
+        reference.set_leaf(i, &leaf);
+        history.push(reference.root().hash);
+    }
+    let (evicted, kept) = history.split_at(history.len() - 32);
+    assert!(kept.iter().all(|root| tree.known_root(root)));
+    assert!(evicted.iter().all(|root| !tree.known_root(root)));
+    assert_eq!(tree.roots_as_vec().len(), 32);
+}
+
+#[test]
+fn invalid_depth_test() {
+    let zeros = MerkleZeros::new(33, 0);
+    assert!(matches!(
+        MerkleTree::new(0, &zeros),
+        Err(ErrorCodes::MerkleErrorInvalidDepth)
+    ));
+    assert!(matches!(
+        MerkleTree::new(33, &zeros),
+        Err(ErrorCodes::MerkleErrorTooLarge)
+    ));
+}
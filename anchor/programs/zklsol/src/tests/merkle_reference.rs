@@ -0,0 +1,125 @@
+use crate::state::merkle_node::MerkleNode;
+use crate::state::merkle_zeros::MerkleZeros;
+
+/// Naive Merkle tree that keeps every leaf and rehashes the whole tree on demand.
+/// Deliberately shares nothing with `MerkleTree` besides the node hashing,
+/// so the two can be compared against each other.
+pub struct ReferenceTree {
+    pub leaves: Vec<MerkleNode>,
+}
+
+impl ReferenceTree {
+    pub fn new(depth: u8, zeros: &MerkleZeros) -> Self {
+        let empty_leaf = MerkleNode::deserialize(&zeros.get(0));
+        let width = 1usize << (depth - 1);
+        Self {
+            leaves: vec![empty_leaf; width],
+        }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    pub fn set_leaf(&mut self, index: u64, data: &[u8; 32]) {
+        self.leaves[index as usize] = MerkleNode::deserialize(data);
+    }
+
+    /// Every level of the tree, leaves first and the root last.
+    pub fn levels(&self) -> Vec<Vec<MerkleNode>> {
+        let mut levels = vec![self.leaves.clone()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| MerkleNode::from_children(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+
+    pub fn root(&self) -> MerkleNode {
+        self.levels().last().unwrap()[0].clone()
+    }
+
+    /// Absolute index of a node, numbering every level left to right starting from the leaves.
+    pub fn absolute_index(&self, level: usize, offset: u64) -> u64 {
+        let mut start = 0u64;
+        let mut width = self.capacity();
+        for _ in 0..level {
+            start += width;
+            width /= 2;
+        }
+        start + offset
+    }
+
+    /// `(index, hash)` pairs in the same order `MerkleTree::insert` reports them:
+    /// left and right node for every level below the root, then the root.
+    pub fn proof(&self, leaf_index: u64) -> Vec<(u64, [u8; 32])> {
+        let levels = self.levels();
+        let mut output = Vec::with_capacity(2 * levels.len());
+        let mut offset = leaf_index;
+        for (level, nodes) in levels.iter().enumerate().take(levels.len() - 1) {
+            let left = offset & !1;
+            let right = left + 1;
+            output.push((self.absolute_index(level, left), nodes[left as usize].hash));
+            output.push((
+                self.absolute_index(level, right),
+                nodes[right as usize].hash,
+            ));
+            offset /= 2;
+        }
+        let root_level = levels.len() - 1;
+        output.push((
+            self.absolute_index(root_level, 0),
+            levels[root_level][0].hash,
+        ));
+        output
+    }
+
+    /// `(index, hash)` of the leaf and each of its ancestors, root last: what
+    /// `MerkleTree::insert` reports as changed.
+    pub fn changed_nodes(&self, leaf_index: u64) -> Vec<(u64, [u8; 32])> {
+        self.levels()
+            .iter()
+            .enumerate()
+            .map(|(level, nodes)| {
+                let offset = leaf_index >> level;
+                (
+                    self.absolute_index(level, offset),
+                    nodes[offset as usize].hash,
+                )
+            })
+            .collect()
+    }
+
+    /// Sibling hashes and 0/1 selectors in the layout the withdraw circuit expects.
+    pub fn path(&self, leaf_index: u64) -> (Vec<[u8; 32]>, Vec<u8>) {
+        let levels = self.levels();
+        let mut elements = Vec::with_capacity(levels.len());
+        let mut indices = Vec::with_capacity(levels.len());
+        let mut offset = leaf_index;
+        for nodes in levels.iter().take(levels.len() - 1) {
+            elements.push(nodes[(offset ^ 1) as usize].hash);
+            indices.push((offset % 2) as u8);
+            offset /= 2;
+        }
+        (elements, indices)
+    }
+
+    /// Recomputes a root from a leaf and its path, used to check `path` itself.
+    pub fn root_from_path(leaf: &[u8; 32], elements: &[[u8; 32]], indices: &[u8]) -> [u8; 32] {
+        let mut current = MerkleNode::deserialize(leaf);
+        for (element, index) in elements.iter().zip(indices) {
+            let sibling = MerkleNode::deserialize(element);
+            current = if *index == 0 {
+                MerkleNode::from_children(&current, &sibling)
+            } else {
+                MerkleNode::from_children(&sibling, &current)
+            };
+        }
+        current.hash
+    }
+}
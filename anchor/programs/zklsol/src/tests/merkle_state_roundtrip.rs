@@ -0,0 +1,241 @@
+use crate::error::ErrorCodes;
+use crate::state::merkle_index::NodeIndex;
+use crate::state::merkle_node::MerkleNode;
+use crate::state::merkle_proof::{LegacyMerkleProof, LegacyPathElement};
+use crate::state::merkle_state::{MerklePendingProofState, MerkleState};
+use crate::state::merkle_tree::MerkleTree;
+use crate::state::merkle_zeros::MerkleZeros;
+use crate::withdraw_verifier::ProofSystem;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn random_hashes(rng: &mut StdRng, len: usize) -> Vec<[u8; 32]> {
+    (0..len)
+        .map(|_| {
+            let mut hash = [0u8; 32];
+            rng.fill(&mut hash);
+            hash
+        })
+        .collect()
+}
+
+pub(super) fn random_state(rng: &mut StdRng) -> MerkleState {
+    let roots = rng.random_range(0..=32);
+    let filled_sub_trees = rng.random_range(0..=32);
+    let mut state = MerkleState {
+        bump: rng.random(),
+        depth: rng.random(),
+        deposit_size: rng.random(),
+        number_of_deposits: rng.random(),
+        current_root_index: rng.random(),
+        next_index: rng.random(),
+        lowest_pending_proof_index: rng.random(),
+        highest_pending_proof_index: rng.random(),
+        mint: Pubkey::new_from_array(rng.random()),
+        roots: random_hashes(rng, roots),
+        filled_sub_trees: random_hashes(rng, filled_sub_trees),
+        verifying_key_hash: rng.random(),
+        circuit_id: rng.random(),
+        deposit_proof_required: rng.random(),
+        deposit_verifying_key_hash: rng.random(),
+        ..Default::default()
+    };
+    if rng.random() {
+        state.proof_system = ProofSystem::Plonk;
+    }
+    state
+}
+
+fn serialize(state: &MerkleState) -> Vec<u8> {
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn merkle_state_roundtrip_test() {
+    let mut rng = StdRng::seed_from_u64(99);
+    for _ in 0..256 {
+        let state = random_state(&mut rng);
+        let data = serialize(&state);
+        assert!(data.len() <= MerkleState::SIZE);
+        let decoded = MerkleState::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(decoded, state);
+    }
+}
+
+#[test]
+fn merkle_state_rejects_truncated_data_test() {
+    let mut rng = StdRng::seed_from_u64(100);
+    let data = serialize(&random_state(&mut rng));
+    for len in [0, 7, 8, data.len() / 2, data.len() - 1] {
+        assert!(MerkleState::try_deserialize(&mut &data[..len]).is_err());
+    }
+}
+
+#[test]
+fn merkle_state_tree_sync_roundtrip_test() {
+    let mut rng = StdRng::seed_from_u64(101);
+    for depth in [1u8, 3, 20, 32] {
+        let zeros = MerkleZeros::new(depth, 0);
+        let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+        let inserts = rng.random_range(0..=tree.number_of_leaves.min(40) + 1);
+        for _ in 0..inserts {
+            let mut leaf = [0u8; 32];
+            rng.fill(&mut leaf);
+            tree.insert(&leaf, &zeros).unwrap();
+        }
+        let mut state = random_state(&mut rng);
+        state.sync(tree);
+        let data = serialize(&state);
+        assert!(data.len() <= MerkleState::SIZE, "depth {}", depth);
+        let decoded = MerkleState::try_deserialize(&mut data.as_slice()).unwrap();
+        let restored = decoded.to_merkle_tree().unwrap();
+        assert_eq!(restored.depth, depth);
+        assert_eq!(restored.next_index, inserts);
+        assert_eq!(restored.roots_as_vec(), state.roots);
+        assert_eq!(restored.filled_sub_trees_as_vec(), state.filled_sub_trees);
+    }
+}
+
+#[test]
+fn merkle_state_tree_reduces_non_canonical_roots_test() {
+    let mut rng = StdRng::seed_from_u64(102);
+    let mut state = random_state(&mut rng);
+    state.depth = 3;
+    state.roots = vec![[0xff; 32]];
+    let tree = state.to_merkle_tree().unwrap();
+    assert_eq!(
+        tree.roots_as_vec(),
+        vec![MerkleNode::mod_input(&[0xff; 32])]
+    );
+    assert!(!tree.known_root(&[0xff; 32]));
+}
+
+#[test]
+fn merkle_state_invalid_depth_test() {
+    let mut rng = StdRng::seed_from_u64(103);
+    let mut state = random_state(&mut rng);
+    for depth in [0u64, 33, 256 + 3, u64::MAX] {
+        state.depth = depth;
+        assert!(state.to_merkle_tree().is_err());
+    }
+}
+
+#[test]
+fn pending_proof_sized_from_depth_test() {
+    let mut rng = StdRng::seed_from_u64(104);
+    for depth in [1u8, 3, 20, 32] {
+        let zeros = MerkleZeros::new(depth, 0);
+        let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+        tree.next_index = rng.random_range(0..=tree.number_of_leaves);
+        let index = tree.next_index;
+        let pending_proof = MerklePendingProofState {
+            bump: rng.random(),
+            depth: depth as u64,
+            index,
+            mint: Pubkey::new_unique(),
+            proof: tree.insert(&[1u8; 32], &zeros).unwrap(),
+        };
+        let mut data = Vec::new();
+        pending_proof.try_serialize(&mut data).unwrap();
+        assert_eq!(
+            data.len(),
+            MerklePendingProofState::size(depth as u64),
+            "depth {}",
+            depth
+        );
+        let decoded = MerklePendingProofState::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(decoded.proof.nodes, pending_proof.proof.nodes);
+        assert_eq!(
+            decoded.node_index(depth - 1).unwrap(),
+            NodeIndex::total_nodes(depth - 1).unwrap() - 1
+        );
+    }
+}
+
+/// Bytes of a pending proof in the `LEGACY_SIZE` layout, siblings included.
+fn legacy_pending_proof(
+    pending_proof: &MerklePendingProofState,
+    siblings: &[MerkleNode],
+) -> Vec<u8> {
+    let height = NodeIndex::height_for_depth(pending_proof.depth as u8).unwrap();
+    let mut path = vec![];
+    for (changed, sibling) in pending_proof.proof.nodes.iter().zip(siblings) {
+        let offset = pending_proof.index >> changed.level;
+        for (offset, node) in [(offset, &changed.node), (offset ^ 1, sibling)] {
+            if let Ok(index) = NodeIndex::new(changed.level, offset).absolute(height) {
+                path.push(LegacyPathElement {
+                    index,
+                    node: node.clone(),
+                });
+            }
+        }
+    }
+    let mut data = MerklePendingProofState::discriminator().to_vec();
+    pending_proof.bump.serialize(&mut data).unwrap();
+    pending_proof.depth.serialize(&mut data).unwrap();
+    pending_proof.index.serialize(&mut data).unwrap();
+    pending_proof.mint.serialize(&mut data).unwrap();
+    LegacyMerkleProof { path }.serialize(&mut data).unwrap();
+    data.resize(MerklePendingProofState::LEGACY_SIZE, 0);
+    data
+}
+
+#[test]
+fn pending_proof_from_legacy_test() {
+    let mut rng = StdRng::seed_from_u64(105);
+    for depth in [1u8, 3, 20] {
+        let zeros = MerkleZeros::new(depth, 0);
+        let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+        tree.next_index = rng.random_range(0..=tree.number_of_leaves);
+        let pending_proof = MerklePendingProofState {
+            bump: rng.random(),
+            depth: depth as u64,
+            index: tree.next_index,
+            mint: Pubkey::new_unique(),
+            proof: tree.insert(&[1u8; 32], &zeros).unwrap(),
+        };
+        let siblings: Vec<MerkleNode> = random_hashes(&mut rng, depth as usize)
+            .into_iter()
+            .map(|hash| MerkleNode { hash })
+            .collect();
+        let data = legacy_pending_proof(&pending_proof, &siblings);
+        let upgraded = MerklePendingProofState::from_legacy(&data).unwrap();
+        assert_eq!(upgraded.bump, pending_proof.bump);
+        assert_eq!(upgraded.depth, pending_proof.depth);
+        assert_eq!(upgraded.index, pending_proof.index);
+        assert_eq!(upgraded.mint, pending_proof.mint);
+        assert_eq!(
+            upgraded.proof.nodes, pending_proof.proof.nodes,
+            "depth {}",
+            depth
+        );
+    }
+}
+
+#[test]
+fn pending_proof_from_legacy_rejects_new_layout_test() {
+    let pending_proof = MerklePendingProofState {
+        bump: 1,
+        depth: 20,
+        index: 0,
+        mint: Pubkey::new_unique(),
+        proof: Default::default(),
+    };
+    let mut data = Vec::new();
+    pending_proof.try_serialize(&mut data).unwrap();
+    data.resize(MerklePendingProofState::size(20), 0);
+    assert!(matches!(
+        MerklePendingProofState::from_legacy(&data),
+        Err(ErrorCodes::NotLegacyPendingProof)
+    ));
+    let mut legacy = legacy_pending_proof(&pending_proof, &[]);
+    legacy[0] ^= 1;
+    assert!(matches!(
+        MerklePendingProofState::from_legacy(&legacy),
+        Err(ErrorCodes::NotLegacyPendingProof)
+    ));
+}
@@ -0,0 +1,14 @@
+mod confidential_balance;
+mod deposit_proof;
+mod deposited_commitment;
+mod groth16;
+mod merkle_differential;
+mod merkle_reference;
+mod merkle_state_roundtrip;
+mod mod_input;
+mod plonk_verifier;
+mod pool_creation;
+mod preflight;
+mod proof_encoding;
+mod query;
+mod withdraw_batch;
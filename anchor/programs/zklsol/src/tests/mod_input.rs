@@ -0,0 +1,97 @@
+use crate::state::merkle_node::MerkleNode;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const BN254_MODULUS: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+fn modulus() -> BigUint {
+    BigUint::parse_bytes(BN254_MODULUS.as_bytes(), 10).unwrap()
+}
+
+fn to_bytes32(value: &BigUint) -> [u8; 32] {
+    let bytes = value.to_bytes_be();
+    let mut output = [0u8; 32];
+    output[32 - bytes.len()..].copy_from_slice(&bytes);
+    output
+}
+
+/// `mod_input` reads the first 32 bytes as a big-endian integer,
+/// shorter inputs are zero padded on the right.
+fn expected_mod_input(input: &[u8]) -> [u8; 32] {
+    let mut data = [0u8; 32];
+    let len = input.len().min(32);
+    data[..len].copy_from_slice(&input[..len]);
+    to_bytes32(&(BigUint::from_bytes_be(&data) % modulus()))
+}
+
+#[test]
+fn test_mod_input() {
+    let p = modulus();
+    let max = (BigUint::one() << 256usize) - BigUint::one();
+    let cases = [
+        BigUint::zero(),
+        BigUint::one(),
+        &p - BigUint::one(),
+        p.clone(),
+        &p + BigUint::one(),
+        &p * BigUint::from(2u8),
+        &p * BigUint::from(5u8) - BigUint::one(),
+        max.clone(),
+    ];
+    for value in cases.iter() {
+        let input = to_bytes32(value);
+        let output = MerkleNode::mod_input(&input);
+        assert_eq!(output, to_bytes32(&(value % &p)), "value {}", value);
+        assert_eq!(MerkleNode::mod_input(&output), output);
+    }
+}
+
+#[test]
+fn mod_input_random_test() {
+    let p = modulus();
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..1024 {
+        let mut input = [0u8; 32];
+        rng.fill(&mut input);
+        let output = MerkleNode::mod_input(&input);
+        assert_eq!(output, expected_mod_input(&input));
+        assert!(BigUint::from_bytes_be(&output) < p);
+        assert_eq!(MerkleNode::deserialize(&input).hash, output);
+    }
+}
+
+#[test]
+fn mod_input_length_edge_cases_test() {
+    let mut rng = StdRng::seed_from_u64(8);
+    for len in [0usize, 1, 16, 31, 32, 33, 64] {
+        let input: Vec<u8> = (0..len).map(|_| rng.random()).collect();
+        assert_eq!(
+            MerkleNode::mod_input(&input),
+            expected_mod_input(&input),
+            "len {}",
+            len
+        );
+    }
+}
+
+#[test]
+fn congruent_inputs_hash_equally_test() {
+    let p = modulus();
+    let value = BigUint::from(123456789u64);
+    let lifted = &value + &p;
+    assert_eq!(
+        MerkleNode::hash(&to_bytes32(&value)),
+        MerkleNode::hash(&to_bytes32(&lifted))
+    );
+    let left = MerkleNode::deserialize(&to_bytes32(&value));
+    let right = MerkleNode {
+        hash: to_bytes32(&lifted),
+    };
+    assert_eq!(
+        MerkleNode::from_children(&left, &right),
+        MerkleNode::from_children(&left, &left)
+    );
+}
@@ -0,0 +1,223 @@
+use crate::error::ErrorCodes;
+use crate::plonk_verifier::{
+    fr_from_be, fr_to_be, lagrange_evaluations, public_input_evaluation, r0, verify_plonk_proof,
+    PlonkChallenges, PlonkEvaluations, PlonkProof, PlonkVerifyingKey, G2_GENERATOR,
+    PLONK_PROOF_SIZE,
+};
+use crate::withdraw_verifier::{verify_withdraw, ProofEncoding, ProofSystem, WithdrawVerifyingKey};
+use ark_bn254::g1::{G1_GENERATOR_X, G1_GENERATOR_Y};
+use ark_bn254::g2::{G2_GENERATOR_X, G2_GENERATOR_Y};
+use ark_bn254::{Fq, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, FftField, Field, PrimeField};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn fq_to_be(value: &Fq) -> [u8; 32] {
+    value.into_bigint().to_bytes_be().try_into().unwrap()
+}
+
+pub(super) fn g1(scalar: &Fr) -> [u8; 64] {
+    let point: G1Affine = (G1Affine::new(G1_GENERATOR_X, G1_GENERATOR_Y) * scalar).into();
+    [fq_to_be(&point.x), fq_to_be(&point.y)]
+        .concat()
+        .try_into()
+        .unwrap()
+}
+
+pub(super) fn g2(scalar: &Fr) -> [u8; 128] {
+    let point: G2Affine = (G2Affine::new(G2_GENERATOR_X, G2_GENERATOR_Y) * scalar).into();
+    [
+        fq_to_be(&point.x.c1),
+        fq_to_be(&point.x.c0),
+        fq_to_be(&point.y.c1),
+        fq_to_be(&point.y.c0),
+    ]
+    .concat()
+    .try_into()
+    .unwrap()
+}
+
+pub(super) fn random_fr(rng: &mut StdRng) -> Fr {
+    let mut bytes = [0u8; 32];
+    rng.fill(&mut bytes);
+    Fr::from_be_bytes_mod_order(&bytes)
+}
+
+struct Forged {
+    vk: PlonkVerifyingKey,
+    proof: PlonkProof,
+    public_inputs: Vec<[u8; 32]>,
+}
+
+/// Builds a proof that satisfies the KZG pairing check using the setup secret `x`,
+/// so the curve side of the verifier can be checked against the same equation in Fr.
+fn forge(seed: u64) -> Forged {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let x = random_fr(&mut rng);
+    let [qm, ql, qr, qo, qc, s1, s2, s3] = [(); 8].map(|_| random_fr(&mut rng));
+    let [a, b, c, z, t1, t2, t3, wxiw] = [(); 8].map(|_| random_fr(&mut rng));
+    let power = 3;
+    let w = Fr::get_root_of_unity(1 << power).unwrap();
+    let (k1, k2) = (Fr::from(2u64), Fr::from(3u64));
+    let vk = PlonkVerifyingKey {
+        n_public: 2,
+        power,
+        k1: fr_to_be(&k1),
+        k2: fr_to_be(&k2),
+        w: fr_to_be(&w),
+        qm: g1(&qm),
+        ql: g1(&ql),
+        qr: g1(&qr),
+        qo: g1(&qo),
+        qc: g1(&qc),
+        s1: g1(&s1),
+        s2: g1(&s2),
+        s3: g1(&s3),
+        x_2: g2(&x),
+    };
+    let public: Vec<Fr> = (0..2).map(|_| random_fr(&mut rng)).collect();
+    let mut proof = PlonkProof {
+        a: g1(&a),
+        b: g1(&b),
+        c: g1(&c),
+        z: g1(&z),
+        t1: g1(&t1),
+        t2: g1(&t2),
+        t3: g1(&t3),
+        wxi: [0u8; 64],
+        wxiw: g1(&wxiw),
+        eval_a: fr_to_be(&random_fr(&mut rng)),
+        eval_b: fr_to_be(&random_fr(&mut rng)),
+        eval_c: fr_to_be(&random_fr(&mut rng)),
+        eval_s1: fr_to_be(&random_fr(&mut rng)),
+        eval_s2: fr_to_be(&random_fr(&mut rng)),
+        eval_zw: [0u8; 32],
+    };
+    // xi doesn't depend on the evaluations, pick eval_zw so the terms in u cancel.
+    let xi = PlonkChallenges::new(
+        &proof,
+        &PlonkEvaluations::new(&proof).unwrap(),
+        &public,
+        &vk,
+    )
+    .xi;
+    proof.eval_zw = fr_to_be(&(z - wxiw * (x - xi * w)));
+
+    let ev = PlonkEvaluations::new(&proof).unwrap();
+    let ch = PlonkChallenges::new(&proof, &ev, &public, &vk);
+    let lagrange = lagrange_evaluations(&ch, &vk).unwrap();
+    let pi = public_input_evaluation(&public, &lagrange);
+    let r0 = r0(&ev, &ch, pi, lagrange[0]);
+    let beta_xi = ch.beta * ch.xi;
+    let d2a = (ev.a + beta_xi + ch.gamma)
+        * (ev.b + beta_xi * k1 + ch.gamma)
+        * (ev.c + beta_xi * k2 + ch.gamma)
+        * ch.alpha;
+    let d3 = (ev.a + ch.beta * ev.s1 + ch.gamma)
+        * (ev.b + ch.beta * ev.s2 + ch.gamma)
+        * ch.alpha
+        * ch.beta
+        * ev.zw;
+    let d = qm * ev.a * ev.b
+        + ql * ev.a
+        + qr * ev.b
+        + qo * ev.c
+        + qc
+        + z * (d2a + lagrange[0] * ch.alpha.square())
+        - s3 * d3
+        - (t1 + ch.xin * t2 + ch.xin.square() * t3) * ch.zh;
+    let f = d + ch.v[1] * a + ch.v[2] * b + ch.v[3] * c + ch.v[4] * s1 + ch.v[5] * s2;
+    let e =
+        -r0 + ch.v[1] * ev.a + ch.v[2] * ev.b + ch.v[3] * ev.c + ch.v[4] * ev.s1 + ch.v[5] * ev.s2;
+    let wxi = (f - e) * (x - ch.xi).inverse().unwrap();
+    proof.wxi = g1(&wxi);
+    Forged {
+        vk,
+        proof,
+        public_inputs: public.iter().map(fr_to_be).collect(),
+    }
+}
+
+#[test]
+fn g2_generator_matches_ark_test() {
+    assert_eq!(G2_GENERATOR, g2(&Fr::from(1u64)));
+}
+
+#[test]
+fn plonk_proof_bytes_roundtrip_test() {
+    let proof = forge(1).proof;
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), PLONK_PROOF_SIZE);
+    assert_eq!(PlonkProof::from_bytes(&bytes), proof);
+    assert_eq!(bytes[..64], proof.a);
+    assert_eq!(bytes[PLONK_PROOF_SIZE - 32..], proof.eval_zw);
+}
+
+#[test]
+fn plonk_verify_test() {
+    for seed in [1, 2, 3] {
+        let forged = forge(seed);
+        verify_plonk_proof(&forged.proof, &forged.public_inputs, &forged.vk).unwrap();
+    }
+}
+
+#[test]
+fn plonk_rejects_tampered_proof_test() {
+    let forged = forge(4);
+    let mut public_inputs = forged.public_inputs.clone();
+    public_inputs[0] = fr_to_be(&(fr_from_be(&public_inputs[0]).unwrap() + Fr::from(1u64)));
+    assert!(matches!(
+        verify_plonk_proof(&forged.proof, &public_inputs, &forged.vk),
+        Err(ErrorCodes::PlonkVerifyError)
+    ));
+
+    let mut proof = forged.proof.clone();
+    proof.eval_a = fr_to_be(&(fr_from_be(&proof.eval_a).unwrap() + Fr::from(1u64)));
+    assert!(matches!(
+        verify_plonk_proof(&proof, &forged.public_inputs, &forged.vk),
+        Err(ErrorCodes::PlonkVerifyError)
+    ));
+
+    let mut proof = forged.proof.clone();
+    proof.eval_b = [0xff; 32];
+    assert!(matches!(
+        verify_plonk_proof(&proof, &forged.public_inputs, &forged.vk),
+        Err(ErrorCodes::PlonkInvalidScalar)
+    ));
+
+    let mut proof = forged.proof.clone();
+    proof.t2[63] ^= 1;
+    assert!(matches!(
+        verify_plonk_proof(&proof, &forged.public_inputs, &forged.vk),
+        Err(ErrorCodes::PlonkCurveError)
+    ));
+
+    assert!(matches!(
+        verify_plonk_proof(&forged.proof, &forged.public_inputs[..1], &forged.vk),
+        Err(ErrorCodes::PlonkInvalidPublicInputs)
+    ));
+}
+
+#[test]
+fn verify_withdraw_dispatches_on_proof_system_test() {
+    let forged = forge(5);
+    let vk: &'static PlonkVerifyingKey = Box::leak(Box::new(forged.vk));
+    let key = WithdrawVerifyingKey::Plonk(vk);
+    assert_eq!(key.proof_system(), ProofSystem::Plonk);
+    let public_inputs: [[u8; 32]; 2] = forged.public_inputs.clone().try_into().unwrap();
+    let proof = ProofEncoding::Plonk(Box::new(forged.proof.to_bytes()));
+    assert_eq!(proof.proof_system(), ProofSystem::Plonk);
+    verify_withdraw(&proof, &public_inputs, &key).unwrap();
+    assert!(matches!(
+        verify_withdraw(
+            &ProofEncoding::Uncompressed([0u8; 256]),
+            &public_inputs,
+            &key
+        ),
+        Err(ErrorCodes::UnsupportedProofSystem)
+    ));
+    assert!(matches!(
+        proof.to_uncompressed(),
+        Err(ErrorCodes::UnsupportedProofSystem)
+    ));
+}
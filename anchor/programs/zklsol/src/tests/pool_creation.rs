@@ -0,0 +1,62 @@
+use super::merkle_state_roundtrip::random_state;
+use crate::error::ErrorCodes;
+use crate::state::merkle_state::MerkleState;
+use crate::withdraw_verifier::{circuit_id, circuit_name, ProofSystem, VERIFYING_KEYS};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn pool(rng: &mut StdRng, depth: u64, proof_system: ProofSystem) -> MerkleState {
+    let mut state = random_state(rng);
+    state.depth = depth;
+    state.proof_system = proof_system;
+    state.deposit_proof_required = false;
+    state
+}
+
+/// `create_merkle` only succeeds for pools `pin_verifying_key` finds a withdraw key for.
+#[test]
+fn pin_verifying_key_per_pool_test() {
+    let mut rng = StdRng::seed_from_u64(150);
+    for depth in [3u64, 20] {
+        let mut state = pool(&mut rng, depth, ProofSystem::Groth16);
+        state.pin_verifying_key().unwrap();
+        assert_eq!(
+            circuit_name(&state.circuit_id),
+            Some(format!("withdraw{}", depth).as_str())
+        );
+        state.verifying_key().unwrap();
+    }
+
+    // Every other combination waits for its key, the pool can't be created before.
+    for proof_system in [ProofSystem::Groth16, ProofSystem::Plonk] {
+        for depth in [3u64, 20, 32] {
+            let keyed = VERIFYING_KEYS
+                .iter()
+                .any(|key| key.depth == depth && key.key.proof_system() == proof_system);
+            let mut state = pool(&mut rng, depth, proof_system);
+            match state.pin_verifying_key() {
+                Ok(()) => assert!(keyed),
+                Err(error) => {
+                    assert!(!keyed);
+                    assert!(matches!(error, ErrorCodes::VerifyingKeyNotFound));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn circuit_id_length_test() {
+    for key in VERIFYING_KEYS.iter() {
+        assert_eq!(circuit_name(&key.circuit_id().unwrap()), Some(key.circuit));
+    }
+    let longest = "w".repeat(32);
+    assert_eq!(
+        circuit_name(&circuit_id(&longest).unwrap()),
+        Some(longest.as_str())
+    );
+    assert!(matches!(
+        circuit_id(&"w".repeat(33)),
+        Err(ErrorCodes::CircuitNameTooLong)
+    ));
+}
@@ -0,0 +1,222 @@
+use crate::error::ErrorCodes;
+use crate::instructions::WithdrawArgs;
+use crate::preflight::{preflight_withdraw, CheckStatus, PreflightInput};
+use crate::state::merkle_state::MerkleState;
+use crate::state::merkle_tree::MerkleTree;
+use crate::state::merkle_zeros::MerkleZeros;
+use crate::withdraw_verifier::{circuit_id, circuit_name, ProofEncoding, ProofSystem};
+use anchor_lang::prelude::Pubkey;
+
+fn merkle_state(depth: u8) -> MerkleState {
+    let zeros = MerkleZeros::new(depth, 0);
+    let mut tree = MerkleTree::new(depth, &zeros).unwrap();
+    tree.insert(&[9u8; 32], &zeros).unwrap();
+    let mut state = MerkleState::from_tree(Pubkey::new_unique(), 1, tree);
+    // Depths without a compiled-in key stay unpinned, like `create_merkle` would refuse them.
+    let _ = state.pin_verifying_key();
+    state
+}
+
+#[test]
+fn preflight_reports_every_failure_test() {
+    let merkle = merkle_state(3);
+    let signer = Pubkey::new_unique();
+    let args = WithdrawArgs {
+        nullifier_hash: [1u8; 32],
+        proof: ProofEncoding::Uncompressed([0u8; 256]),
+        root: [2u8; 32],
+    };
+    let report = preflight_withdraw(&PreflightInput {
+        args: &args,
+        signer: &signer,
+        recipient: &signer,
+        merkle: &merkle,
+        nullifier_account_exists: true,
+    });
+    assert!(report.merkle_state.is_passed());
+    assert!(matches!(
+        report.known_root,
+        CheckStatus::Failed(ErrorCodes::MerkleErrorUnknownRoot)
+    ));
+    assert!(matches!(
+        report.signer_not_recipient,
+        CheckStatus::Failed(ErrorCodes::SignerCantMatchRecipient)
+    ));
+    assert!(matches!(
+        report.proof_a,
+        CheckStatus::Failed(ErrorCodes::DeserializeWithMode)
+    ));
+    assert!(report.proof_encoding.is_passed());
+    assert!(report.verifying_key.is_passed());
+    assert!(matches!(report.verification, CheckStatus::Skipped));
+    assert!(matches!(
+        report.nullifier_unspent,
+        CheckStatus::Failed(ErrorCodes::NullifierAlreadySpent)
+    ));
+    assert!(!report.is_ok());
+    assert!(matches!(
+        report.first_error(),
+        Some(ErrorCodes::MerkleErrorUnknownRoot)
+    ));
+}
+
+#[test]
+fn preflight_unknown_depth_test() {
+    let merkle = merkle_state(5);
+    let args = WithdrawArgs {
+        nullifier_hash: [1u8; 32],
+        proof: ProofEncoding::Uncompressed([0u8; 256]),
+        root: merkle.roots[1],
+    };
+    let report = preflight_withdraw(&PreflightInput {
+        args: &args,
+        signer: &Pubkey::new_unique(),
+        recipient: &Pubkey::new_unique(),
+        merkle: &merkle,
+        nullifier_account_exists: false,
+    });
+    assert!(report.known_root.is_passed());
+    assert!(report.signer_not_recipient.is_passed());
+    assert!(report.nullifier_unspent.is_passed());
+    assert!(matches!(
+        report.verifying_key,
+        CheckStatus::Failed(ErrorCodes::VerifyingKeyNotFound)
+    ));
+    assert!(matches!(report.verification, CheckStatus::Skipped));
+}
+
+#[test]
+fn preflight_corrupt_state_test() {
+    let mut merkle = merkle_state(3);
+    merkle.depth = 300;
+    let args = WithdrawArgs {
+        nullifier_hash: [1u8; 32],
+        proof: ProofEncoding::Uncompressed([0u8; 256]),
+        root: merkle.roots[0],
+    };
+    let report = preflight_withdraw(&PreflightInput {
+        args: &args,
+        signer: &Pubkey::new_unique(),
+        recipient: &Pubkey::new_unique(),
+        merkle: &merkle,
+        nullifier_account_exists: false,
+    });
+    assert!(matches!(
+        report.merkle_state,
+        CheckStatus::Failed(ErrorCodes::InvalidMerkleDepth)
+    ));
+    assert!(matches!(report.known_root, CheckStatus::Skipped));
+}
+
+#[test]
+fn preflight_invalid_compressed_proof_test() {
+    let merkle = merkle_state(3);
+    let args = WithdrawArgs {
+        nullifier_hash: [1u8; 32],
+        proof: ProofEncoding::Compressed([0xffu8; 128]),
+        root: merkle.roots[1],
+    };
+    let report = preflight_withdraw(&PreflightInput {
+        args: &args,
+        signer: &Pubkey::new_unique(),
+        recipient: &Pubkey::new_unique(),
+        merkle: &merkle,
+        nullifier_account_exists: false,
+    });
+    assert!(matches!(
+        report.proof_encoding,
+        CheckStatus::Failed(ErrorCodes::InvalidCompressedProof)
+    ));
+    assert!(matches!(report.proof_a, CheckStatus::Skipped));
+    assert!(matches!(report.verification, CheckStatus::Skipped));
+    assert!(matches!(
+        report.first_error(),
+        Some(ErrorCodes::InvalidCompressedProof)
+    ));
+}
+
+#[test]
+fn preflight_plonk_pool_test() {
+    let mut merkle = merkle_state(3);
+    merkle.proof_system = ProofSystem::Plonk;
+    let args = WithdrawArgs {
+        nullifier_hash: [1u8; 32],
+        proof: ProofEncoding::Uncompressed([0u8; 256]),
+        root: merkle.roots[1],
+    };
+    let report = preflight_withdraw(&PreflightInput {
+        args: &args,
+        signer: &Pubkey::new_unique(),
+        recipient: &Pubkey::new_unique(),
+        merkle: &merkle,
+        nullifier_account_exists: false,
+    });
+    assert!(matches!(
+        report.proof_encoding,
+        CheckStatus::Failed(ErrorCodes::UnsupportedProofSystem)
+    ));
+    assert!(matches!(report.proof_a, CheckStatus::Skipped));
+    // No PLONK key is compiled in for this depth.
+    assert!(matches!(
+        report.verifying_key,
+        CheckStatus::Failed(ErrorCodes::VerifyingKeyNotFound)
+    ));
+    assert!(matches!(report.verification, CheckStatus::Skipped));
+}
+
+#[test]
+fn preflight_verifying_key_mismatch_test() {
+    let mut merkle = merkle_state(3);
+    assert_eq!(circuit_name(&merkle.circuit_id), Some("withdraw3"));
+    let args = WithdrawArgs {
+        nullifier_hash: [1u8; 32],
+        proof: ProofEncoding::Uncompressed([0u8; 256]),
+        root: merkle.roots[1],
+    };
+    let pinned = merkle.verifying_key_hash;
+    for (hash, circuit) in [
+        ([0u8; 32], merkle.circuit_id),
+        (pinned, [0u8; 32]),
+        ([1u8; 32], merkle.circuit_id),
+        (pinned, circuit_id("withdraw20").unwrap()),
+    ] {
+        merkle.verifying_key_hash = hash;
+        merkle.circuit_id = circuit;
+        let report = preflight_withdraw(&PreflightInput {
+            args: &args,
+            signer: &Pubkey::new_unique(),
+            recipient: &Pubkey::new_unique(),
+            merkle: &merkle,
+            nullifier_account_exists: false,
+        });
+        assert!(matches!(
+            report.verifying_key,
+            CheckStatus::Failed(ErrorCodes::VerifyingKeyMismatch)
+        ));
+        assert!(matches!(report.verification, CheckStatus::Skipped));
+    }
+}
+
+#[test]
+fn preflight_legacy_pool_test() {
+    // Pools from before pinning decode with both fields zero.
+    let mut merkle = merkle_state(3);
+    let pinned = merkle.verifying_key_hash;
+    merkle.verifying_key_hash = [0u8; 32];
+    merkle.circuit_id = [0u8; 32];
+    assert!(merkle.is_legacy_pin());
+    assert_eq!(merkle.verifying_key().unwrap().key.hash(), pinned);
+    let args = WithdrawArgs {
+        nullifier_hash: [1u8; 32],
+        proof: ProofEncoding::Uncompressed([0u8; 256]),
+        root: merkle.roots[1],
+    };
+    let report = preflight_withdraw(&PreflightInput {
+        args: &args,
+        signer: &Pubkey::new_unique(),
+        recipient: &Pubkey::new_unique(),
+        merkle: &merkle,
+        nullifier_account_exists: false,
+    });
+    assert!(matches!(report.verifying_key, CheckStatus::Passed));
+}
@@ -0,0 +1,67 @@
+use crate::error::ErrorCodes;
+use crate::withdraw3_verifying_key::VERIFYINGKEY;
+use crate::withdraw_verifier::{
+    compress_proof, decompress_proof, ProofEncoding, COMPRESSED_PROOF_SIZE, PROOF_SIZE,
+};
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+
+/// Valid curve points in the uncompressed proof layout, taken from the verifying key.
+fn sample_proof() -> [u8; PROOF_SIZE] {
+    let mut proof = [0u8; PROOF_SIZE];
+    proof[..64].copy_from_slice(&VERIFYINGKEY.vk_alpha_g1);
+    proof[64..192].copy_from_slice(&VERIFYINGKEY.vk_beta_g2);
+    proof[192..].copy_from_slice(&VERIFYINGKEY.vk_ic[1]);
+    proof
+}
+
+#[test]
+fn compress_roundtrip_test() {
+    let proof = sample_proof();
+    let compressed = compress_proof(&proof).unwrap();
+    assert_eq!(decompress_proof(&compressed).unwrap(), proof);
+    assert_eq!(
+        ProofEncoding::compress(&proof)
+            .unwrap()
+            .to_uncompressed()
+            .unwrap(),
+        proof
+    );
+    assert_eq!(
+        ProofEncoding::Uncompressed(proof)
+            .to_uncompressed()
+            .unwrap(),
+        proof
+    );
+}
+
+#[test]
+fn proof_encoding_tag_test() {
+    let proof = sample_proof();
+    let uncompressed = ProofEncoding::Uncompressed(proof).try_to_vec().unwrap();
+    let compressed = ProofEncoding::compress(&proof)
+        .unwrap()
+        .try_to_vec()
+        .unwrap();
+    assert_eq!(uncompressed.len(), 1 + PROOF_SIZE);
+    assert_eq!(compressed.len(), 1 + COMPRESSED_PROOF_SIZE);
+    assert_eq!((uncompressed[0], compressed[0]), (0, 1));
+    assert_eq!(
+        ProofEncoding::deserialize(&mut compressed.as_slice()).unwrap(),
+        ProofEncoding::compress(&proof).unwrap()
+    );
+    assert!(ProofEncoding::deserialize(&mut [2u8; 129].as_slice()).is_err());
+}
+
+#[test]
+fn invalid_compressed_proof_test() {
+    let mut compressed = compress_proof(&sample_proof()).unwrap();
+    compressed[32..96].copy_from_slice(&[0xff; 64]);
+    assert!(matches!(
+        decompress_proof(&compressed),
+        Err(ErrorCodes::InvalidCompressedProof)
+    ));
+    assert!(matches!(
+        ProofEncoding::Compressed(compressed).to_uncompressed(),
+        Err(ErrorCodes::InvalidCompressedProof)
+    ));
+}
@@ -0,0 +1,81 @@
+use super::merkle_state_roundtrip::random_state;
+use crate::instructions::{PoolInfo, VerifyingKeyInfo};
+use crate::state::merkle_tree::MerkleTree;
+use crate::state::merkle_zeros::MerkleZeros;
+use crate::withdraw_verifier::{circuit_name, ProofSystem};
+use anchor_lang::solana_program::program::MAX_RETURN_DATA;
+use anchor_lang::AnchorSerialize;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[test]
+fn pool_info_current_root_test() {
+    let mut rng = StdRng::seed_from_u64(140);
+    let zeros = MerkleZeros::new(20, 0);
+    let mut tree = MerkleTree::new(20, &zeros).unwrap();
+    let mut state = random_state(&mut rng);
+    for _ in 0..3 {
+        let mut leaf = [0u8; 32];
+        rng.fill(&mut leaf[1..]);
+        tree.insert(&leaf, &zeros).unwrap();
+    }
+    let latest = tree.roots_as_vec()[tree.current_root_index as usize];
+    state.sync(tree);
+
+    let info = PoolInfo::from(&state);
+    assert_eq!(info.current_root, latest);
+    assert_eq!(info.depth, 20);
+    assert_eq!(info.next_index, 3);
+    assert_eq!(info.deposit_size, state.deposit_size);
+    assert_eq!(info.number_of_deposits, state.number_of_deposits);
+    assert!(state
+        .to_merkle_tree()
+        .unwrap()
+        .known_root(&info.current_root));
+
+    state.roots.clear();
+    assert_eq!(PoolInfo::from(&state).current_root, [0u8; 32]);
+}
+
+#[test]
+fn verifying_key_info_test() {
+    let mut rng = StdRng::seed_from_u64(141);
+    let mut state = random_state(&mut rng);
+    state.depth = 20;
+    state.proof_system = ProofSystem::Groth16;
+    state.deposit_proof_required = false;
+    state.pin_verifying_key().unwrap();
+
+    let info = VerifyingKeyInfo::from(&state);
+    assert!(info.verifying_key_matches);
+    assert_eq!(info.verifying_key_hash, state.verifying_key_hash);
+    assert_eq!(circuit_name(&info.circuit_id), Some("withdraw20"));
+
+    state.verifying_key_hash[0] ^= 1;
+    assert!(!VerifyingKeyInfo::from(&state).verifying_key_matches);
+}
+
+#[test]
+fn verifying_key_info_legacy_pool_test() {
+    let mut rng = StdRng::seed_from_u64(143);
+    let mut state = random_state(&mut rng);
+    state.depth = 20;
+    state.proof_system = ProofSystem::Groth16;
+    // Created before keys were pinned, withdrawals fall back to the depth's key.
+    state.circuit_id = [0u8; 32];
+    state.verifying_key_hash = [0u8; 32];
+
+    let info = VerifyingKeyInfo::from(&state);
+    assert!(info.verifying_key_matches);
+    assert_eq!(circuit_name(&info.circuit_id), None);
+}
+
+#[test]
+fn query_return_data_fits_test() {
+    let mut rng = StdRng::seed_from_u64(142);
+    let state = random_state(&mut rng);
+    let pool_info = PoolInfo::from(&state).try_to_vec().unwrap();
+    let key_info = VerifyingKeyInfo::from(&state).try_to_vec().unwrap();
+    assert!(pool_info.len() <= MAX_RETURN_DATA);
+    assert!(key_info.len() <= MAX_RETURN_DATA);
+}
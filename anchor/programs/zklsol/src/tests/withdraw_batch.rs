@@ -0,0 +1,138 @@
+use super::groth16;
+use crate::error::ErrorCodes;
+use crate::plonk_verifier::PlonkVerifyingKey;
+use crate::withdraw_verifier::{
+    batch_weights, verify_withdraw_batch, ProofEncoding, WithdrawVerifyingKey,
+    MAX_BATCH_WITHDRAWALS, WITHDRAW_PUBLIC_INPUTS,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+type PublicInputs = [[u8; 32]; WITHDRAW_PUBLIC_INPUTS];
+
+type Setup = groth16::Setup<WITHDRAW_PUBLIC_INPUTS>;
+
+fn batch(setup: &Setup, rng: &mut StdRng, count: usize) -> (Vec<ProofEncoding>, Vec<PublicInputs>) {
+    (0..count)
+        .map(|i| {
+            let (proof, inputs) = setup.prove_random(rng);
+            let proof = if i % 2 == 0 {
+                ProofEncoding::Uncompressed(proof)
+            } else {
+                ProofEncoding::compress(&proof).unwrap()
+            };
+            (proof, inputs)
+        })
+        .unzip()
+}
+
+#[test]
+fn withdraw_batch_verify_test() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let setup = Setup::new(&mut rng);
+    let key = WithdrawVerifyingKey::Groth16(setup.key);
+    for count in 1..=MAX_BATCH_WITHDRAWALS {
+        let (proofs, inputs) = batch(&setup, &mut rng, count);
+        verify_withdraw_batch(&proofs, &inputs, &key).unwrap();
+    }
+}
+
+#[test]
+fn withdraw_batch_rejects_invalid_proof_test() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let setup = Setup::new(&mut rng);
+    let key = WithdrawVerifyingKey::Groth16(setup.key);
+    let (proofs, inputs) = batch(&setup, &mut rng, 3);
+
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[1].swap(0, 1);
+    assert!(matches!(
+        verify_withdraw_batch(&proofs, &wrong_inputs, &key),
+        Err(ErrorCodes::Groth16VerifyError)
+    ));
+
+    // Two invalid proofs whose errors would cancel in an unweighted sum.
+    let mut swapped = proofs.clone();
+    let mut swapped_inputs = inputs.clone();
+    let (first, first_inputs) = setup.prove_random(&mut rng);
+    let (second, second_inputs) = setup.prove_random(&mut rng);
+    swapped_inputs[0] = first_inputs;
+    swapped_inputs[2] = second_inputs;
+    swapped[0] =
+        ProofEncoding::Uncompressed([&first[..192], &second[192..]].concat().try_into().unwrap());
+    swapped[2] =
+        ProofEncoding::Uncompressed([&second[..192], &first[192..]].concat().try_into().unwrap());
+    assert!(matches!(
+        verify_withdraw_batch(&swapped, &swapped_inputs, &key),
+        Err(ErrorCodes::Groth16VerifyError)
+    ));
+
+    let mut unreduced = inputs.clone();
+    unreduced[0][0] = [0xff; 32];
+    assert!(matches!(
+        verify_withdraw_batch(&proofs, &unreduced, &key),
+        Err(ErrorCodes::Groth16VerifyError)
+    ));
+}
+
+#[test]
+fn withdraw_batch_size_and_proof_system_test() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let setup = Setup::new(&mut rng);
+    let key = WithdrawVerifyingKey::Groth16(setup.key);
+    let (proofs, inputs) = batch(&setup, &mut rng, MAX_BATCH_WITHDRAWALS + 1);
+    assert!(matches!(
+        verify_withdraw_batch(&proofs, &inputs, &key),
+        Err(ErrorCodes::InvalidBatchSize)
+    ));
+    assert!(matches!(
+        verify_withdraw_batch(&[], &[], &key),
+        Err(ErrorCodes::InvalidBatchSize)
+    ));
+    assert!(matches!(
+        verify_withdraw_batch(&proofs[..2], &inputs[..1], &key),
+        Err(ErrorCodes::Groth16CreateError)
+    ));
+
+    let plonk: &'static PlonkVerifyingKey = Box::leak(Box::new(PlonkVerifyingKey {
+        n_public: WITHDRAW_PUBLIC_INPUTS,
+        power: 3,
+        k1: [0u8; 32],
+        k2: [0u8; 32],
+        w: [0u8; 32],
+        qm: [0u8; 64],
+        ql: [0u8; 64],
+        qr: [0u8; 64],
+        qo: [0u8; 64],
+        qc: [0u8; 64],
+        s1: [0u8; 64],
+        s2: [0u8; 64],
+        s3: [0u8; 64],
+        x_2: [0u8; 128],
+    }));
+    assert!(matches!(
+        verify_withdraw_batch(
+            &proofs[..2],
+            &inputs[..2],
+            &WithdrawVerifyingKey::Plonk(plonk)
+        ),
+        Err(ErrorCodes::UnsupportedProofSystem)
+    ));
+}
+
+#[test]
+fn batch_weights_bind_every_proof_test() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let setup = Setup::new(&mut rng);
+    let (first, first_inputs) = setup.prove_random(&mut rng);
+    let (second, second_inputs) = setup.prove_random(&mut rng);
+    let weights = batch_weights(&[first, second], &[first_inputs, second_inputs]);
+    assert_eq!(weights.len(), 2);
+    assert_ne!(weights[0], weights[1]);
+    let mut changed = second_inputs;
+    changed[0][31] ^= 1;
+    assert_ne!(
+        batch_weights(&[first, second], &[first_inputs, changed])[0],
+        weights[0]
+    );
+}
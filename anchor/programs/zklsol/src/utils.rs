@@ -10,6 +10,7 @@ use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
 use anchor_lang::solana_program::program_memory::sol_memset;
 use anchor_spl::associated_token::get_associated_token_address;
 use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
 use num_bigint::BigInt;
 use sha2::{Digest, Sha256};
 use std::cell::RefCell;
@@ -99,6 +100,35 @@ pub fn transfer_token_pda<'a>(
     Ok(())
 }
 
+/// `transfer_checked` through either token program, Token-2022 mints included.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_token_checked<'a>(
+    from: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    owner: AccountInfo<'a>,
+    amount: u64,
+    decimals: u8,
+    seeds: &[&[&[u8]]],
+) -> Result<()> {
+    solana_program::program::invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            &token_program.key(),
+            &from.key(),
+            &mint.key(),
+            &to.key(),
+            &owner.key(),
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[from, mint, to, token_program, owner],
+        seeds,
+    )?;
+    Ok(())
+}
+
 pub fn close_account(from: &mut AccountInfo, to: &mut AccountInfo) -> Result<()> {
     let amount = from.lamports();
     let size = from.try_data_len()?;
@@ -120,7 +150,7 @@ pub fn burn_tokens<'a>(
         account.key,
         mint.key,
         authority.key,
-        &[&authority.key],
+        &[authority.key],
         amount,
     )?;
     solana_program::program::invoke_signed(&ix, &[account, mint, authority, token_program], seeds)?;
@@ -181,7 +211,7 @@ pub fn change_endianness(bytes: &[u8]) -> Vec<u8> {
     vec
 }
 
-pub fn clone_account_info<'a>(original: &AccountInfo<'a>) -> AccountInfo<'static> {
+pub fn clone_account_info(original: &AccountInfo<'_>) -> AccountInfo<'static> {
     // Clone and leak the data
     let new_data_vec = original.data.borrow().to_vec();
     let new_data_slice = Box::leak(Box::new(new_data_vec)).as_mut_slice();
@@ -209,7 +239,7 @@ pub fn clone_account_info<'a>(original: &AccountInfo<'a>) -> AccountInfo<'static
     }
 }
 
-pub fn unsafe_clone_account_info<'a, 'info>(input: &'a AccountInfo<'info>) -> AccountInfo<'static> {
+pub fn unsafe_clone_account_info(input: &AccountInfo<'_>) -> AccountInfo<'static> {
     unsafe { std::mem::transmute::<AccountInfo, AccountInfo>(input.clone()) }
 }
 
@@ -235,21 +265,47 @@ pub fn create_pda_account<'a, 'info>(
     let seeds = combined_seeds.as_slice();
 
     let lamports = Rent::get()?.minimum_balance(space);
-    let ix = solana_program::system_instruction::create_account(
-        payer.key,
-        target_account.key,
-        lamports,
-        space as u64,
-        program_id,
-    );
-
+    let current_lamports = target_account.lamports();
+    if current_lamports == 0 {
+        let ix = solana_program::system_instruction::create_account(
+            payer.key,
+            target_account.key,
+            lamports,
+            space as u64,
+            program_id,
+        );
+        solana_program::program::invoke_signed(
+            &ix,
+            &[
+                payer.clone(),
+                target_account.clone(),
+                system_program.clone(),
+            ],
+            &[seeds],
+        )?;
+        return Ok(());
+    }
+    // Someone sent lamports to the address first, `create_account` would refuse it.
+    // Same as Anchor's `init`: top up, then allocate and assign.
+    let top_up = lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(payer.key, target_account.key, top_up),
+            &[
+                payer.clone(),
+                target_account.clone(),
+                system_program.clone(),
+            ],
+        )?;
+    }
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::allocate(target_account.key, space as u64),
+        &[target_account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
     solana_program::program::invoke_signed(
-        &ix,
-        &[
-            payer.clone(),
-            target_account.clone(),
-            system_program.clone(),
-        ],
+        &solana_program::system_instruction::assign(target_account.key, program_id),
+        &[target_account.clone(), system_program.clone()],
         &[seeds],
     )?;
     Ok(())
@@ -0,0 +1,418 @@
+use crate::error::ErrorCodes;
+use crate::plonk_verifier::{
+    fr_from_be, fr_to_be, verify_plonk_proof, PlonkProof, PlonkVerifyingKey, PLONK_PROOF_SIZE,
+};
+use crate::utils::change_endianness;
+use crate::withdraw20_verifying_key::VERIFYINGKEY as VERIFYINGKEY20;
+use crate::withdraw3_verifying_key::VERIFYINGKEY as VERIFYINGKEY3;
+use anchor_lang::prelude::{AnchorDeserialize, AnchorSerialize, Pubkey};
+use anchor_lang::solana_program::{hash, keccak};
+use ark_bn254::Fr;
+use ark_ff::{One, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use groth16_solana::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use solana_bn254::compression::prelude::{
+    alt_bn128_g1_compress, alt_bn128_g1_decompress, alt_bn128_g2_compress, alt_bn128_g2_decompress,
+};
+use solana_bn254::prelude::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+use std::ops::Neg;
+
+type G1 = ark_bn254::g1::G1Affine;
+
+pub const WITHDRAW_PUBLIC_INPUTS: usize = 2;
+pub const DEPOSIT_PUBLIC_INPUTS: usize = 1;
+pub const PROOF_SIZE: usize = 256;
+pub const COMPRESSED_PROOF_SIZE: usize = 128;
+/// Bound on `withdraw_batch`, one more proof costs a pairing and two scalar muls.
+pub const MAX_BATCH_WITHDRAWALS: usize = 4;
+
+/// Proving system a pool's withdrawals are verified with, fixed at `create_merkle`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProofSystem {
+    #[default]
+    Groth16,
+    Plonk,
+}
+
+/// Proof as sent in `WithdrawArgs`, the Borsh variant index is the format tag.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ProofEncoding {
+    /// Groth16 a(x, y) | b(x.c1, x.c0, y.c1, y.c0) | c(x, y), 32 byte big-endian coordinates.
+    Uncompressed([u8; PROOF_SIZE]),
+    /// Groth16 a | b | c in alt_bn128 compressed form, 32 + 64 + 32 bytes.
+    Compressed([u8; COMPRESSED_PROOF_SIZE]),
+    /// snarkjs PLONK proof, see `PlonkProof`. Boxed to keep it off the stack.
+    Plonk(Box<[u8; PLONK_PROOF_SIZE]>),
+}
+
+impl ProofEncoding {
+    pub fn proof_system(&self) -> ProofSystem {
+        match self {
+            Self::Uncompressed(_) | Self::Compressed(_) => ProofSystem::Groth16,
+            Self::Plonk(_) => ProofSystem::Plonk,
+        }
+    }
+
+    pub fn compress(proof: &[u8; PROOF_SIZE]) -> Result<Self, ErrorCodes> {
+        Ok(Self::Compressed(compress_proof(proof)?))
+    }
+
+    pub fn to_uncompressed(&self) -> Result<[u8; PROOF_SIZE], ErrorCodes> {
+        match self {
+            Self::Uncompressed(proof) => Ok(*proof),
+            Self::Compressed(proof) => decompress_proof(proof),
+            Self::Plonk(_) => Err(ErrorCodes::UnsupportedProofSystem),
+        }
+    }
+}
+
+pub fn compress_proof(proof: &[u8; PROOF_SIZE]) -> Result<[u8; COMPRESSED_PROOF_SIZE], ErrorCodes> {
+    let mut output = [0u8; COMPRESSED_PROOF_SIZE];
+    let proof_a = alt_bn128_g1_compress(&proof[0..64]).map_err(|_| ErrorCodes::ExtractProofA)?;
+    let proof_b = alt_bn128_g2_compress(&proof[64..192]).map_err(|_| ErrorCodes::ExtractProofB)?;
+    let proof_c = alt_bn128_g1_compress(&proof[192..256]).map_err(|_| ErrorCodes::ExtractProofC)?;
+    output[0..32].copy_from_slice(&proof_a);
+    output[32..96].copy_from_slice(&proof_b);
+    output[96..128].copy_from_slice(&proof_c);
+    Ok(output)
+}
+
+/// Uses the alt_bn128 compression syscalls on-chain.
+pub fn decompress_proof(
+    proof: &[u8; COMPRESSED_PROOF_SIZE],
+) -> Result<[u8; PROOF_SIZE], ErrorCodes> {
+    let mut output = [0u8; PROOF_SIZE];
+    let proof_a =
+        alt_bn128_g1_decompress(&proof[0..32]).map_err(|_| ErrorCodes::InvalidCompressedProof)?;
+    let proof_b =
+        alt_bn128_g2_decompress(&proof[32..96]).map_err(|_| ErrorCodes::InvalidCompressedProof)?;
+    let proof_c =
+        alt_bn128_g1_decompress(&proof[96..128]).map_err(|_| ErrorCodes::InvalidCompressedProof)?;
+    output[0..64].copy_from_slice(&proof_a);
+    output[64..192].copy_from_slice(&proof_b);
+    output[192..256].copy_from_slice(&proof_c);
+    Ok(output)
+}
+
+/// Public signals of the withdraw circuit: `recipientOut`, `nullifierHashOut`.
+pub fn withdraw_public_inputs(
+    recipient: &Pubkey,
+    nullifier_hash: &[u8; 32],
+) -> [[u8; 32]; WITHDRAW_PUBLIC_INPUTS] {
+    [recipient.to_bytes(), *nullifier_hash]
+}
+
+/// Public signal of the deposit circuit: `commitment`, the leaf about to be inserted.
+pub fn deposit_public_inputs(
+    commitment: &[u8; 32],
+) -> Result<[[u8; 32]; DEPOSIT_PUBLIC_INPUTS], ErrorCodes> {
+    fr_from_be(commitment).map_err(|_| ErrorCodes::ValueCantBePoseidonHashed)?;
+    Ok([*commitment])
+}
+
+pub enum WithdrawVerifyingKey {
+    Groth16(&'static Groth16Verifyingkey<'static>),
+    Plonk(&'static PlonkVerifyingKey),
+}
+
+impl WithdrawVerifyingKey {
+    pub fn proof_system(&self) -> ProofSystem {
+        match self {
+            Self::Groth16(_) => ProofSystem::Groth16,
+            Self::Plonk(_) => ProofSystem::Plonk,
+        }
+    }
+
+    /// The fingerprint `MerkleState::verifying_key_hash` pins, see `groth16_key_hash`
+    /// and `plonk_key_hash`.
+    pub fn hash(&self) -> [u8; 32] {
+        match self {
+            Self::Groth16(key) => groth16_key_hash(
+                &key.vk_alpha_g1,
+                &key.vk_beta_g2,
+                &key.vk_gamme_g2,
+                &key.vk_delta_g2,
+                key.vk_ic,
+            ),
+            Self::Plonk(key) => plonk_key_hash(key),
+        }
+    }
+}
+
+/// sha256 over the key points in the byte layout the verifier consumes. The number of
+/// public inputs is implied by the length of `vk_ic`.
+pub fn groth16_key_hash(
+    vk_alpha_g1: &[u8; 64],
+    vk_beta_g2: &[u8; 128],
+    vk_gamme_g2: &[u8; 128],
+    vk_delta_g2: &[u8; 128],
+    vk_ic: &[[u8; 64]],
+) -> [u8; 32] {
+    let mut parts: Vec<&[u8]> = vec![
+        b"groth16".as_slice(),
+        vk_alpha_g1.as_slice(),
+        vk_beta_g2.as_slice(),
+        vk_gamme_g2.as_slice(),
+        vk_delta_g2.as_slice(),
+    ];
+    parts.extend(vk_ic.iter().map(|ic| ic.as_slice()));
+    hash::hashv(&parts).to_bytes()
+}
+
+/// sha256 over every field of a PLONK key, sizes little-endian.
+pub fn plonk_key_hash(key: &PlonkVerifyingKey) -> [u8; 32] {
+    hash::hashv(&[
+        b"plonk",
+        &(key.n_public as u64).to_le_bytes(),
+        &key.power.to_le_bytes(),
+        &key.k1,
+        &key.k2,
+        &key.w,
+        &key.qm,
+        &key.ql,
+        &key.qr,
+        &key.qo,
+        &key.qc,
+        &key.s1,
+        &key.s2,
+        &key.s3,
+        &key.x_2,
+    ])
+    .to_bytes()
+}
+
+/// Circuit name as stored in `MerkleState::circuit_id`, zero padded.
+pub fn circuit_id(circuit: &str) -> Result<[u8; 32], ErrorCodes> {
+    let mut id = [0u8; 32];
+    id.get_mut(..circuit.len())
+        .ok_or(ErrorCodes::CircuitNameTooLong)?
+        .copy_from_slice(circuit.as_bytes());
+    Ok(id)
+}
+
+/// Inverse of `circuit_id`, `None` for a pool that never pinned a key.
+pub fn circuit_name(id: &[u8; 32]) -> Option<&str> {
+    let len = id.iter().position(|b| *b == 0).unwrap_or(id.len());
+    match len {
+        0 => None,
+        _ => std::str::from_utf8(&id[..len]).ok(),
+    }
+}
+
+/// A withdraw verifying key compiled into the program and the circuit it belongs to.
+pub struct VerifyingKeyMetadata {
+    pub circuit: &'static str,
+    pub depth: u64,
+    pub key: WithdrawVerifyingKey,
+}
+
+impl VerifyingKeyMetadata {
+    pub fn circuit_id(&self) -> Result<[u8; 32], ErrorCodes> {
+        circuit_id(self.circuit)
+    }
+}
+
+/// Every key a pool can withdraw with. PLONK keys go here once the
+/// `snarkjs plonk setup` output is converted with `zklsol-snarkjs plonk-vk-to-rs`.
+pub static VERIFYING_KEYS: [VerifyingKeyMetadata; 2] = [
+    VerifyingKeyMetadata {
+        circuit: "withdraw3",
+        depth: 3,
+        key: WithdrawVerifyingKey::Groth16(&VERIFYINGKEY3),
+    },
+    VerifyingKeyMetadata {
+        circuit: "withdraw20",
+        depth: 20,
+        key: WithdrawVerifyingKey::Groth16(&VERIFYINGKEY20),
+    },
+];
+
+pub fn verifying_key(
+    depth: u64,
+    proof_system: ProofSystem,
+) -> Result<&'static VerifyingKeyMetadata, ErrorCodes> {
+    find_verifying_key(&VERIFYING_KEYS, depth, proof_system)
+}
+
+/// Keys of `circuits/deposit_commitment.circom`, depth 0 as one key serves every tree.
+/// Empty until `zklsol-circuits build deposit_commitment` has produced its key, pools
+/// requiring deposit proofs can't be created before.
+pub static DEPOSIT_VERIFYING_KEYS: [VerifyingKeyMetadata; 0] = [];
+
+pub fn deposit_verifying_key(
+    proof_system: ProofSystem,
+) -> Result<&'static VerifyingKeyMetadata, ErrorCodes> {
+    find_verifying_key(&DEPOSIT_VERIFYING_KEYS, 0, proof_system)
+}
+
+fn find_verifying_key(
+    keys: &'static [VerifyingKeyMetadata],
+    depth: u64,
+    proof_system: ProofSystem,
+) -> Result<&'static VerifyingKeyMetadata, ErrorCodes> {
+    keys.iter()
+        .find(|entry| entry.depth == depth && entry.key.proof_system() == proof_system)
+        .ok_or(ErrorCodes::VerifyingKeyNotFound)
+}
+
+/// snarkjs emits proof_a big-endian, the verifier wants it negated.
+pub fn negate_proof_a(proof: &[u8; PROOF_SIZE]) -> Result<[u8; 64], ErrorCodes> {
+    let proof_a: G1 = G1::deserialize_with_mode(
+        &*[&change_endianness(&proof[0..64]), &[0u8][..]].concat(),
+        Compress::No,
+        Validate::Yes,
+    )
+    .map_err(|_| ErrorCodes::DeserializeWithMode)?;
+    let mut proof_a_neg = [0u8; 65];
+    proof_a
+        .neg()
+        .x
+        .serialize_with_mode(&mut proof_a_neg[..32], Compress::No)
+        .map_err(|_| ErrorCodes::SerializeWithMode)?;
+    proof_a
+        .neg()
+        .y
+        .serialize_with_mode(&mut proof_a_neg[32..], Compress::No)
+        .map_err(|_| ErrorCodes::SerializeWithMode)?;
+    change_endianness(&proof_a_neg[..64])
+        .try_into()
+        .map_err(|_| ErrorCodes::ExtractProofA)
+}
+
+/// Also verifies `deposit_commitment` proofs, `N` is the circuit's number of public inputs.
+pub fn verify_withdraw_proof<const N: usize>(
+    proof: &[u8; PROOF_SIZE],
+    public_inputs: &[[u8; 32]; N],
+    verifying_key: &Groth16Verifyingkey,
+) -> Result<(), ErrorCodes> {
+    let proof_a = negate_proof_a(proof)?;
+    let proof_b = proof[64..192]
+        .try_into()
+        .map_err(|_| ErrorCodes::ExtractProofB)?;
+    let proof_c = proof[192..256]
+        .try_into()
+        .map_err(|_| ErrorCodes::ExtractProofC)?;
+    let mut verifier =
+        Groth16Verifier::new(&proof_a, &proof_b, &proof_c, public_inputs, verifying_key)
+            .map_err(|_| ErrorCodes::Groth16CreateError)?;
+    verifier
+        .verify()
+        .map_err(|_| ErrorCodes::Groth16VerifyError)?;
+    Ok(())
+}
+
+/// Dispatches on the pool's key, a proof for another proving system is rejected.
+pub fn verify_withdraw<const N: usize>(
+    proof: &ProofEncoding,
+    public_inputs: &[[u8; 32]; N],
+    verifying_key: &WithdrawVerifyingKey,
+) -> Result<(), ErrorCodes> {
+    match (verifying_key, proof) {
+        (WithdrawVerifyingKey::Groth16(key), ProofEncoding::Uncompressed(_))
+        | (WithdrawVerifyingKey::Groth16(key), ProofEncoding::Compressed(_)) => {
+            verify_withdraw_proof(&proof.to_uncompressed()?, public_inputs, key)
+        }
+        (WithdrawVerifyingKey::Plonk(key), ProofEncoding::Plonk(bytes)) => {
+            verify_plonk_proof(&PlonkProof::from_bytes(bytes), public_inputs, key)
+        }
+        _ => Err(ErrorCodes::UnsupportedProofSystem),
+    }
+}
+
+/// Weights for `verify_withdraw_batch`, derived from every proof and public input
+/// so a prover can't choose proofs after seeing them. 128 bits each.
+pub fn batch_weights(
+    proofs: &[[u8; PROOF_SIZE]],
+    public_inputs: &[[[u8; 32]; WITHDRAW_PUBLIC_INPUTS]],
+) -> Vec<Fr> {
+    let mut data = Vec::with_capacity(proofs.len() * (PROOF_SIZE + 32 * WITHDRAW_PUBLIC_INPUTS));
+    for (proof, inputs) in proofs.iter().zip(public_inputs) {
+        data.extend_from_slice(proof);
+        inputs
+            .iter()
+            .for_each(|input| data.extend_from_slice(input));
+    }
+    let seed = keccak::hash(&data).to_bytes();
+    (0..proofs.len() as u32)
+        .map(|i| {
+            let hash = keccak::hashv(&[&seed, &i.to_le_bytes()]).to_bytes();
+            Fr::from(u128::from_le_bytes(hash[..16].try_into().unwrap()))
+        })
+        .collect()
+}
+
+fn g1_add(p: &[u8; 64], q: &[u8; 64]) -> Result<[u8; 64], ErrorCodes> {
+    let output = alt_bn128_addition(&[&p[..], &q[..]].concat())
+        .map_err(|_| ErrorCodes::Groth16VerifyError)?;
+    output
+        .try_into()
+        .map_err(|_| ErrorCodes::Groth16VerifyError)
+}
+
+fn g1_mul(p: &[u8; 64], scalar: &Fr) -> Result<[u8; 64], ErrorCodes> {
+    let output = alt_bn128_multiplication(&[&p[..], &fr_to_be(scalar)[..]].concat())
+        .map_err(|_| ErrorCodes::Groth16VerifyError)?;
+    output
+        .try_into()
+        .map_err(|_| ErrorCodes::Groth16VerifyError)
+}
+
+/// Checks K Groth16 withdraw proofs with one multi-pairing. With weights r_i:
+/// prod e(-r_i A_i, B_i) * e(sum(r_i) alpha, beta) * e(sum(r_i vk_x_i), gamma)
+/// * e(sum(r_i C_i), delta) == 1, K + 3 pairings instead of 4K.
+pub fn verify_withdraw_batch(
+    proofs: &[ProofEncoding],
+    public_inputs: &[[[u8; 32]; WITHDRAW_PUBLIC_INPUTS]],
+    verifying_key: &WithdrawVerifyingKey,
+) -> Result<(), ErrorCodes> {
+    let WithdrawVerifyingKey::Groth16(key) = verifying_key else {
+        return Err(ErrorCodes::UnsupportedProofSystem);
+    };
+    if proofs.is_empty() || proofs.len() > MAX_BATCH_WITHDRAWALS {
+        return Err(ErrorCodes::InvalidBatchSize);
+    }
+    if proofs.len() != public_inputs.len() || key.vk_ic.len() != WITHDRAW_PUBLIC_INPUTS + 1 {
+        return Err(ErrorCodes::Groth16CreateError);
+    }
+    let proofs = proofs
+        .iter()
+        .map(ProofEncoding::to_uncompressed)
+        .collect::<Result<Vec<_>, ErrorCodes>>()?;
+    let weights = batch_weights(&proofs, public_inputs);
+
+    // sum(r_i vk_x_i) = ic_0 sum(r_i) + sum_j ic_j+1 sum_i(r_i input_ij), sums taken in Fr.
+    let weight_sum: Fr = weights.iter().sum();
+    let mut input_sums = [Fr::zero(); WITHDRAW_PUBLIC_INPUTS];
+    for (inputs, weight) in public_inputs.iter().zip(&weights) {
+        for (sum, input) in input_sums.iter_mut().zip(inputs) {
+            *sum += fr_from_be(input).map_err(|_| ErrorCodes::Groth16VerifyError)? * weight;
+        }
+    }
+    let mut vk_x = g1_mul(&key.vk_ic[0], &weight_sum)?;
+    for (ic, sum) in key.vk_ic[1..].iter().zip(&input_sums) {
+        vk_x = g1_add(&vk_x, &g1_mul(ic, sum)?)?;
+    }
+
+    let mut pairing_input = Vec::with_capacity((proofs.len() + 3) * 192);
+    let mut proof_c = [0u8; 64];
+    for (proof, weight) in proofs.iter().zip(&weights) {
+        let proof_a: &[u8; 64] = proof[0..64].try_into().unwrap();
+        let c: &[u8; 64] = proof[192..256].try_into().unwrap();
+        pairing_input.extend_from_slice(&g1_mul(proof_a, &-*weight)?);
+        pairing_input.extend_from_slice(&proof[64..192]);
+        proof_c = g1_add(&proof_c, &g1_mul(c, weight)?)?;
+    }
+    for (g1, g2) in [
+        (g1_mul(&key.vk_alpha_g1, &weight_sum)?, &key.vk_beta_g2),
+        (vk_x, &key.vk_gamme_g2),
+        (proof_c, &key.vk_delta_g2),
+    ] {
+        pairing_input.extend_from_slice(&g1);
+        pairing_input.extend_from_slice(g2);
+    }
+    let result = alt_bn128_pairing(&pairing_input).map_err(|_| ErrorCodes::Groth16VerifyError)?;
+    if result != fr_to_be(&Fr::one()) {
+        return Err(ErrorCodes::Groth16VerifyError);
+    }
+    Ok(())
+}
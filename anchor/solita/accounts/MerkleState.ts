@@ -8,6 +8,7 @@
 import * as beet from '@metaplex-foundation/beet'
 import * as web3 from '@solana/web3.js'
 import * as beetSolana from '@metaplex-foundation/beet-solana'
+import { ProofSystem, proofSystemBeet } from '../types/ProofSystem'
 
 /**
  * Arguments used to create {@link MerkleState}
@@ -26,6 +27,11 @@ export type MerkleStateArgs = {
   mint: web3.PublicKey
   roots: number[] /* size: 32 */[]
   filledSubTrees: number[] /* size: 32 */[]
+  proofSystem: ProofSystem
+  verifyingKeyHash: number[] /* size: 32 */
+  circuitId: number[] /* size: 32 */
+  depositProofRequired: boolean
+  depositVerifyingKeyHash: number[] /* size: 32 */
 }
 
 export const merkleStateDiscriminator = [206, 169, 210, 196, 54, 110, 7, 217]
@@ -48,7 +54,12 @@ export class MerkleState implements MerkleStateArgs {
     readonly highestPendingProofIndex: beet.bignum,
     readonly mint: web3.PublicKey,
     readonly roots: number[] /* size: 32 */[],
-    readonly filledSubTrees: number[] /* size: 32 */[]
+    readonly filledSubTrees: number[] /* size: 32 */[],
+    readonly proofSystem: ProofSystem,
+    readonly verifyingKeyHash: number[] /* size: 32 */,
+    readonly circuitId: number[] /* size: 32 */,
+    readonly depositProofRequired: boolean,
+    readonly depositVerifyingKeyHash: number[] /* size: 32 */
   ) {}
 
   /**
@@ -66,7 +77,12 @@ export class MerkleState implements MerkleStateArgs {
       args.highestPendingProofIndex,
       args.mint,
       args.roots,
-      args.filledSubTrees
+      args.filledSubTrees,
+      args.proofSystem,
+      args.verifyingKeyHash,
+      args.circuitId,
+      args.depositProofRequired,
+      args.depositVerifyingKeyHash
     )
   }
 
@@ -246,6 +262,11 @@ export class MerkleState implements MerkleStateArgs {
       mint: this.mint.toBase58(),
       roots: this.roots,
       filledSubTrees: this.filledSubTrees,
+      proofSystem: 'ProofSystem.' + ProofSystem[this.proofSystem],
+      verifyingKeyHash: this.verifyingKeyHash,
+      circuitId: this.circuitId,
+      depositProofRequired: this.depositProofRequired,
+      depositVerifyingKeyHash: this.depositVerifyingKeyHash,
     }
   }
 }
@@ -273,6 +294,11 @@ export const merkleStateBeet = new beet.FixableBeetStruct<
     ['mint', beetSolana.publicKey],
     ['roots', beet.array(beet.uniformFixedSizeArray(beet.u8, 32))],
     ['filledSubTrees', beet.array(beet.uniformFixedSizeArray(beet.u8, 32))],
+    ['proofSystem', proofSystemBeet],
+    ['verifyingKeyHash', beet.uniformFixedSizeArray(beet.u8, 32)],
+    ['circuitId', beet.uniformFixedSizeArray(beet.u8, 32)],
+    ['depositProofRequired', beet.bool],
+    ['depositVerifyingKeyHash', beet.uniformFixedSizeArray(beet.u8, 32)],
   ],
   MerkleState.fromArgs,
   'MerkleState'
@@ -8,3 +8,4 @@ export const MerklePendingProofSeed = "MerklePendingProof";
 export const UserAddressLookupTableSeed = "UserAddressLookupTable";
 
 export const NullifierHashSeed = "NullifierHash";
+export const DepositedCommitmentSeed = "DepositedCommitment";
@@ -23,7 +23,7 @@ export type DepositInstructionArgs = {
  * @category Deposit
  * @category generated
  */
-export const depositStruct = new beet.BeetArgsStruct<
+export const depositStruct = new beet.FixableBeetArgsStruct<
   DepositInstructionArgs & {
     instructionDiscriminator: number[] /* size: 8 */
   }
@@ -43,6 +43,7 @@ export const depositStruct = new beet.BeetArgsStruct<
  * @property [_writable_] merkleTokenAccount
  * @property [] merkleZeros
  * @property [_writable_] pendingProof
+ * @property [_writable_] depositedCommitment
  * @property [] mint
  * @property [] associatedTokenProgram
  * @category Instructions
@@ -56,6 +57,7 @@ export type DepositInstructionAccounts = {
   merkleTokenAccount: web3.PublicKey
   merkleZeros: web3.PublicKey
   pendingProof: web3.PublicKey
+  depositedCommitment: web3.PublicKey
   tokenProgram?: web3.PublicKey
   mint: web3.PublicKey
   associatedTokenProgram: web3.PublicKey
@@ -118,6 +120,11 @@ export function createDepositInstruction(
       isWritable: true,
       isSigner: false,
     },
+    {
+      pubkey: accounts.depositedCommitment,
+      isWritable: true,
+      isSigner: false,
+    },
     {
       pubkey: accounts.tokenProgram ?? splToken.TOKEN_PROGRAM_ID,
       isWritable: false,
@@ -23,7 +23,7 @@ export type WithdrawInstructionArgs = {
  * @category Withdraw
  * @category generated
  */
-export const withdrawStruct = new beet.BeetArgsStruct<
+export const withdrawStruct = new beet.FixableBeetArgsStruct<
   WithdrawInstructionArgs & {
     instructionDiscriminator: number[] /* size: 8 */
   }
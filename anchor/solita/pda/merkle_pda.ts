@@ -15,6 +15,7 @@ import {
   MerkleTokenSeed,
   MerkleZerosSeed,
   NullifierHashSeed,
+  DepositedCommitmentSeed,
 } from "../constants/seeds";
 import BN from "bn.js";
 import { CryptoHelper } from "../crypto-helpers";
@@ -42,6 +43,24 @@ export async function getMerkleAccount(
   return await MerkleState.fromAccountAddress(connection, merkle);
 }
 
+/**
+ * The verifying key a pool was created for: the sha256 fingerprint printed by
+ * `zklsol-snarkjs vk-hash` and the withdraw circuit name.
+ */
+export async function getMerkleVerifyingKey(
+  connection: Connection,
+  depth: number,
+  mint: PublicKey
+): Promise<{ verifyingKeyHash: string; circuit: string }> {
+  const merkle = await getMerkleAccount(connection, depth, mint);
+  const circuitId = Buffer.from(merkle.circuitId);
+  const end = circuitId.indexOf(0);
+  return {
+    verifyingKeyHash: Buffer.from(merkle.verifyingKeyHash).toString("hex"),
+    circuit: circuitId.subarray(0, end < 0 ? circuitId.length : end).toString(),
+  };
+}
+
 export function getMerkleTokenAddress(
   depth: number,
   mint: PublicKey
@@ -119,6 +138,20 @@ export function getMerkleNodeAddress(
   );
 }
 
+/**
+ * Absolute index of the ancestor of `leafIndex` on `level`, the index a pending
+ * proof's changed node is written to.
+ */
+export function getChangedNodeIndex(
+  depth: number,
+  leafIndex: number,
+  level: number
+): number {
+  const height = depth - 1;
+  const levelStart = 2 ** (height + 1) - 2 ** (height - level + 1);
+  return levelStart + Math.floor(leafIndex / 2 ** level);
+}
+
 export async function getMerkleNodeAccount(
   connection: Connection,
   depth: number,
@@ -128,6 +161,25 @@ export async function getMerkleNodeAccount(
   return await MerkleNodeState.fromAccountAddress(connection, merkle);
 }
 
+/**
+ * Hash of the node at `index` on `level`. Only nodes a pending proof changed have
+ * an account, any other node still holds the level's zero hash.
+ */
+export async function getMerkleNodeHash(
+  connection: Connection,
+  depth: number,
+  index: number,
+  level: number,
+  zeros: MerkleZeros
+): Promise<number[]> {
+  const [node] = getMerkleNodeAddress(depth, index);
+  const accountInfo = await connection.getAccountInfo(node);
+  if (accountInfo == null) {
+    return zeros.zeros[level];
+  }
+  return MerkleNodeState.fromAccountInfo(accountInfo, 0)[0].data;
+}
+
 export function getNullifierHashAddress(
   depth: number,
   nullifier_hash: number[]
@@ -142,6 +194,20 @@ export function getNullifierHashAddress(
   );
 }
 
+export function getDepositedCommitmentAddress(
+  merkle: PublicKey,
+  commitment: number[]
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(anchor.utils.bytes.utf8.encode(DepositedCommitmentSeed)),
+      merkle.toBuffer(),
+      Buffer.from(commitment),
+    ],
+    PROGRAM_ID
+  );
+}
+
 export async function getNullifierHashAccount(
   connection: Connection,
   depth: number,
@@ -11,7 +11,7 @@ import {
 } from "@solana/web3.js";
 import assert from "assert";
 import {Program} from "@coral-xyz/anchor";
-import {createAssociatedTokenAccountInstruction, getAssociatedTokenAddress, NATIVE_MINT} from "@solana/spl-token";
+import {createAssociatedTokenAccountInstruction, getAssociatedTokenAddress, NATIVE_MINT, TOKEN_PROGRAM_ID} from "@solana/spl-token";
 
 
 export async function processAndValidateTransaction(
@@ -136,12 +136,14 @@ export async function getOrCreateTokenAccountInstruction(
     mint: PublicKey,
     user: PublicKey,
     connection: Connection,
-    payer: PublicKey | null = null
+    payer: PublicKey | null = null,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<TransactionInstruction | null> {
     const userTokenAccountAddress = await getAssociatedTokenAddress(
         mint,
         user,
-        false
+        false,
+        tokenProgram
     )
     const userTokenAccount = await connection.getParsedAccountInfo(
         userTokenAccountAddress
@@ -151,7 +153,8 @@ export async function getOrCreateTokenAccountInstruction(
             payer ? payer : user,
             userTokenAccountAddress,
             user,
-            mint
+            mint,
+            tokenProgram
         )
     } else {
         return null
@@ -7,8 +7,8 @@
 
 import * as beet from '@metaplex-foundation/beet'
 import { MerkleNode, merkleNodeBeet } from './MerkleNode'
-export type PathElement = {
-  index: beet.bignum
+export type ChangedNode = {
+  level: number
   node: MerkleNode
 }
 
@@ -16,10 +16,10 @@ export type PathElement = {
  * @category userTypes
  * @category generated
  */
-export const pathElementBeet = new beet.BeetArgsStruct<PathElement>(
+export const changedNodeBeet = new beet.BeetArgsStruct<ChangedNode>(
   [
-    ['index', beet.u64],
+    ['level', beet.u8],
     ['node', merkleNodeBeet],
   ],
-  'PathElement'
+  'ChangedNode'
 )
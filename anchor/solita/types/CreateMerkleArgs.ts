@@ -6,9 +6,12 @@
  */
 
 import * as beet from '@metaplex-foundation/beet'
+import { ProofSystem, proofSystemBeet } from './ProofSystem'
 export type CreateMerkleArgs = {
   depth: beet.bignum
   depositSize: beet.bignum
+  proofSystem: ProofSystem
+  requireDepositProof: boolean
 }
 
 /**
@@ -19,6 +22,8 @@ export const createMerkleArgsBeet = new beet.BeetArgsStruct<CreateMerkleArgs>(
   [
     ['depth', beet.u64],
     ['depositSize', beet.u64],
+    ['proofSystem', proofSystemBeet],
+    ['requireDepositProof', beet.bool],
   ],
   'CreateMerkleArgs'
 )
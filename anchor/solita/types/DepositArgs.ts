@@ -6,15 +6,22 @@
  */
 
 import * as beet from '@metaplex-foundation/beet'
+import { ProofEncoding, proofEncodingBeet } from './ProofEncoding'
 export type DepositArgs = {
   input: number[] /* size: 32 */
+  memo: beet.COption<Uint8Array>
+  proof: beet.COption<ProofEncoding>
 }
 
 /**
  * @category userTypes
  * @category generated
  */
-export const depositArgsBeet = new beet.BeetArgsStruct<DepositArgs>(
-  [['input', beet.uniformFixedSizeArray(beet.u8, 32)]],
+export const depositArgsBeet = new beet.FixableBeetArgsStruct<DepositArgs>(
+  [
+    ['input', beet.uniformFixedSizeArray(beet.u8, 32)],
+    ['memo', beet.coption(beet.bytes)],
+    ['proof', beet.coption(proofEncodingBeet)],
+  ],
   'DepositArgs'
 )
@@ -6,9 +6,9 @@
  */
 
 import * as beet from '@metaplex-foundation/beet'
-import { PathElement, pathElementBeet } from './PathElement'
+import { ChangedNode, changedNodeBeet } from './ChangedNode'
 export type MerkleProof = {
-  path: PathElement[]
+  nodes: ChangedNode[]
 }
 
 /**
@@ -16,6 +16,6 @@ export type MerkleProof = {
  * @category generated
  */
 export const merkleProofBeet = new beet.FixableBeetArgsStruct<MerkleProof>(
-  [['path', beet.array(pathElementBeet)]],
+  [['nodes', beet.array(changedNodeBeet)]],
   'MerkleProof'
 )
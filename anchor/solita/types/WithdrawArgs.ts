@@ -6,9 +6,10 @@
  */
 
 import * as beet from '@metaplex-foundation/beet'
+import { ProofEncoding, proofEncodingBeet } from './ProofEncoding'
 export type WithdrawArgs = {
   nullifierHash: number[] /* size: 32 */
-  proof: number[] /* size: 256 */
+  proof: ProofEncoding
   root: number[] /* size: 32 */
 }
 
@@ -16,10 +17,10 @@ export type WithdrawArgs = {
  * @category userTypes
  * @category generated
  */
-export const withdrawArgsBeet = new beet.BeetArgsStruct<WithdrawArgs>(
+export const withdrawArgsBeet = new beet.FixableBeetArgsStruct<WithdrawArgs>(
   [
     ['nullifierHash', beet.uniformFixedSizeArray(beet.u8, 32)],
-    ['proof', beet.uniformFixedSizeArray(beet.u8, 256)],
+    ['proof', proofEncodingBeet],
     ['root', beet.uniformFixedSizeArray(beet.u8, 32)],
   ],
   'WithdrawArgs'
@@ -1,8 +1,10 @@
+export * from './ChangedNode'
 export * from './CreateAddressLookupTableArgs'
 export * from './CreateMerkleArgs'
 export * from './CreateMerkleNodeArgs'
 export * from './DepositArgs'
 export * from './MerkleNode'
 export * from './MerkleProof'
-export * from './PathElement'
+export * from './ProofEncoding'
+export * from './ProofSystem'
 export * from './WithdrawArgs'
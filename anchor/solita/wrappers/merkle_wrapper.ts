@@ -16,12 +16,14 @@ import {
 } from "../instructions";
 import {
   getMerkleAccount,
+  getChangedNodeIndex,
   getMerkleAddress,
   getMerkleNodeAddress,
   getMerklePendingProofAddress,
   getMerkleTokenAddress,
   getMerkleZerosAddress,
   getNullifierHashAddress,
+  getDepositedCommitmentAddress,
 } from "../pda/merkle_pda";
 import {
   getOrCreateTokenAccountInstruction,
@@ -34,7 +36,9 @@ import {
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
+import { ProofSystem } from "../types";
 
 export type GenerateProofPath = number[][];
 
@@ -43,6 +47,9 @@ export type BuildCreateMerkleTransactionInstructionInputs = {
   depth: number;
   depositSize: number;
   mint: PublicKey;
+  proofSystem?: ProofSystem;
+  requireDepositProof?: boolean;
+  tokenProgram?: PublicKey;
 };
 
 export function buildCreateMerkleTransactionInstruction({
@@ -50,13 +57,21 @@ export function buildCreateMerkleTransactionInstruction({
   depth,
   depositSize,
   mint,
+  proofSystem = ProofSystem.Groth16,
+  requireDepositProof = false,
+  tokenProgram = TOKEN_PROGRAM_ID,
 }: BuildCreateMerkleTransactionInstructionInputs): TransactionInstruction {
   const [merkle] = getMerkleAddress(depth, mint);
   const [merkleZeros] = getMerkleZerosAddress(depth, mint);
   const [merkleTokenAccount] = getMerkleTokenAddress(depth, mint);
 
   const args: CreateMerkleInstructionArgs = {
-    args: { depth, depositSize },
+    args: {
+      depth,
+      depositSize,
+      proofSystem,
+      requireDepositProof,
+    },
   };
   const accounts: CreateMerkleInstructionAccounts = {
     signer,
@@ -64,6 +79,7 @@ export function buildCreateMerkleTransactionInstruction({
     merkleZeros,
     merkleTokenAccount,
     mint,
+    tokenProgram,
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
   };
   return createCreateMerkleInstruction(accounts, args);
@@ -75,6 +91,9 @@ export type BuildDepositTransactionInstructionInputs = {
   depth: number;
   connection: Connection;
   mint: PublicKey;
+  memo?: Uint8Array | null;
+  proof?: number[] | null;
+  tokenProgram?: PublicKey;
 };
 
 export async function buildDepositTransactionInstruction({
@@ -83,6 +102,9 @@ export async function buildDepositTransactionInstruction({
   depth,
   connection,
   mint,
+  memo = null,
+  proof = null,
+  tokenProgram = TOKEN_PROGRAM_ID,
 }: BuildDepositTransactionInstructionInputs): Promise<
   TransactionInstruction[]
 > {
@@ -90,11 +112,18 @@ export async function buildDepositTransactionInstruction({
   const [merkle] = getMerkleAddress(depth, mint);
   const merkleAccount = await getMerkleAccount(connection, depth, mint);
   const [merkleTokenAccount] = getMerkleTokenAddress(depth, mint);
-  const signerTokenAccount = getAssociatedTokenAddressSync(mint, signer);
+  const signerTokenAccount = getAssociatedTokenAddressSync(
+    mint,
+    signer,
+    false,
+    tokenProgram
+  );
   const getOrCreateInstruction = await getOrCreateTokenAccountInstruction(
     mint,
     signer,
-    connection
+    connection,
+    null,
+    tokenProgram
   );
   if (getOrCreateInstruction) {
     instructions.push(getOrCreateInstruction);
@@ -106,9 +135,12 @@ export async function buildDepositTransactionInstruction({
     Number(merkleAccount.nextIndex),
     mint
   );
+  const [depositedCommitment] = getDepositedCommitmentAddress(merkle, input);
   const args: DepositInstructionArgs = {
     args: {
       input,
+      memo,
+      proof: proof ? { __kind: "Uncompressed", fields: [proof] } : null,
     },
   };
   const accounts: DepositInstructionAccounts = {
@@ -120,6 +152,8 @@ export async function buildDepositTransactionInstruction({
     merkle,
     merkleZeros,
     pendingProof,
+    depositedCommitment,
+    tokenProgram,
   };
   instructions.push(createDepositInstruction(accounts, args));
   return instructions;
@@ -154,7 +188,7 @@ export async function buildWithdrawTransactionInstruction({
   const args: WithdrawInstructionArgs = {
     args: {
       nullifierHash,
-      proof,
+      proof: { __kind: "Uncompressed", fields: [proof] },
       root,
     },
   };
@@ -217,7 +251,7 @@ export async function buildDumpProofTransactionInstructionsArray({
       Number(proofAccount.index),
       mint
     );
-    for (let i = 0; i < proofAccount.proof.path.length; i += 5) {
+    for (let i = 0; i < proofAccount.proof.nodes.length; i += 5) {
       const startIndex = i;
       const endIndex = startIndex + 5;
       const instruction = await buildDumpProofTransactionInstruction({
@@ -259,9 +293,12 @@ export async function buildDumpProofTransactionInstruction({
 }: BuildDumpProofTransactionInstructionInputs): Promise<TransactionInstruction> {
   const remainingAccounts: PublicKey[] = [];
   let counter = 0;
-  for (let i = 0; i < proofAccount.proof.path.length; i++) {
-    const node = proofAccount.proof.path[i];
-    const [merkleNode] = getMerkleNodeAddress(depth, node.index as number);
+  for (let i = 0; i < proofAccount.proof.nodes.length; i++) {
+    const node = proofAccount.proof.nodes[i];
+    const [merkleNode] = getMerkleNodeAddress(
+      depth,
+      getChangedNodeIndex(depth, Number(proofAccount.index), node.level)
+    );
     if (i < startIndex || i > endIndex) {
       continue;
     }
@@ -18,11 +18,12 @@ import {
 import { CryptoHelper, Rand } from "../solita/crypto-helpers";
 import {
   getMerkleAccount,
-  getMerkleNodeAccount,
+  getMerkleNodeHash,
+  getMerkleZerosAccount,
   isgNullifierHashUsed,
 } from "../solita/pda/merkle_pda";
 import { run_circuit, ZkHelper } from "../solita/zk-helper";
-import { NATIVE_MINT } from "@solana/spl-token";
+import { createMint, NATIVE_MINT } from "@solana/spl-token";
 
 const signer = Keypair.generate();
 const anonSigner = Keypair.generate();
@@ -141,7 +142,29 @@ describe("ZKL-$SOL - merkle test", () => {
     }
   });
 
+  it("Reads unwritten nodes as zeros", async () => {
+    const zeros = await getMerkleZerosAccount(
+      program.provider.connection,
+      depth,
+      NATIVE_MINT
+    );
+    // The sibling of the last deposit, no pending proof has changed it.
+    const node = await getMerkleNodeHash(
+      program.provider.connection,
+      depth,
+      deposits,
+      0,
+      zeros
+    );
+    assert.deepEqual(node, zeros.zeros[0]);
+  });
+
   it("Withdraw", async () => {
+    const zeros = await getMerkleZerosAccount(
+      program.provider.connection,
+      depth,
+      NATIVE_MINT
+    );
     for (let j = 0; j < deposits; j++) {
       const proof_path: GenerateProofPath = CryptoHelper.generate_proof_path(
         depth,
@@ -157,16 +180,18 @@ describe("ZKL-$SOL - merkle test", () => {
       );
       const pathElements: bigint[] = [];
       const pathIndices: (0 | 1)[] = [];
-      for (const p of proof_path) {
+      for (const [level, p] of proof_path.entries()) {
         if (p.length > 2) {
           const is_left = p[2] == 0 ? 0 : 1;
           const index = is_left ? p[0] : p[1];
-          const node = await getMerkleNodeAccount(
+          const node = await getMerkleNodeHash(
             program.provider.connection,
             depth,
-            index
+            index,
+            level,
+            zeros
           );
-          pathElements.push(CryptoHelper.numberArrayToBigInt(node.data));
+          pathElements.push(CryptoHelper.numberArrayToBigInt(node));
           pathIndices.push(is_left);
         }
       }
@@ -213,4 +238,71 @@ describe("ZKL-$SOL - merkle test", () => {
       );
     }
   });
+
+  it("Refuses deposit proofs until the deposit_commitment key exists", async () => {
+    const commitment = CryptoHelper.from_children(
+      CryptoHelper.modInput(CryptoHelper.generateAndPrepareRand(789).u8Array),
+      CryptoHelper.modInput(CryptoHelper.generateAndPrepareRand(987).u8Array)
+    );
+    const depositInstructions = await buildDepositTransactionInstruction({
+      signer: signer.publicKey,
+      input: commitment,
+      depth,
+      connection: program.provider.connection,
+      mint: NATIVE_MINT,
+      proof: new Array(256).fill(0),
+    });
+    const sig = await processTransaction(
+      [modifyComputeUnits, ...depositInstructions],
+      program.provider.connection,
+      signer
+    );
+    const txn = await program.provider.connection.getParsedTransaction(
+      sig.Signature,
+      {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      }
+    );
+    assert.notEqual(sig.SignatureResult.err, null);
+    assert.ok(
+      txn?.meta?.logMessages.some((log) =>
+        log.includes("VerifyingKeyNotFound")
+      ),
+      `${txn?.meta?.logMessages.join("\n")}`
+    );
+  });
+
+  it("Refuses pools requiring deposit proofs until the key exists", async () => {
+    const mint = await createMint(
+      program.provider.connection,
+      signer,
+      signer.publicKey,
+      null,
+      9
+    );
+    const instruction = buildCreateMerkleTransactionInstruction({
+      mint,
+      signer: signer.publicKey,
+      depth,
+      depositSize: LAMPORTS_PER_SOL,
+      requireDepositProof: true,
+    });
+    const sig = await processTransaction(
+      [modifyComputeUnits, instruction],
+      program.provider.connection,
+      signer
+    );
+    const txn = await program.provider.connection.getParsedTransaction(
+      sig.Signature,
+      "confirmed"
+    );
+    assert.notEqual(sig.SignatureResult.err, null);
+    assert.ok(
+      txn?.meta?.logMessages.some((log) =>
+        log.includes("VerifyingKeyNotFound")
+      ),
+      `${txn?.meta?.logMessages.join("\n")}`
+    );
+  });
 });
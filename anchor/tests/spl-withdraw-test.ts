@@ -0,0 +1,199 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Zklsol } from "../target/types/zklsol";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import assert from "assert";
+import {
+  airdrop,
+  modifyComputeUnits,
+  processTransaction,
+} from "../solita/sol-helpers";
+import {
+  buildCreateMerkleTransactionInstruction,
+  buildDepositTransactionInstruction,
+  buildDumpProofTransactionInstructionsArray,
+  buildWithdrawTransactionInstruction,
+  GenerateProofPath,
+} from "../solita/wrappers/merkle_wrapper";
+import { CryptoHelper } from "../solita/crypto-helpers";
+import {
+  getMerkleAccount,
+  getMerkleNodeHash,
+  getMerkleZerosAccount,
+} from "../solita/pda/merkle_pda";
+import { run_circuit, ZkHelper } from "../solita/zk-helper";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+const signer = Keypair.generate();
+const anonSigner = Keypair.generate();
+// Nodes and nullifiers are keyed by depth, merkle-test uses depth 20.
+const depth = 3;
+const depositSize = 1_000_000;
+const CIRCUIT_NAME = `withdraw${depth}`;
+const secret = CryptoHelper.generateAndPrepareRand(321);
+const nullifier = CryptoHelper.generateAndPrepareRand(654);
+
+describe("ZKL-$SOL - SPL token withdraw test", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Zklsol as Program<Zklsol>;
+  let mint: PublicKey;
+  let signerTokenAccount: PublicKey;
+
+  async function assertProcessed(
+    instructions: anchor.web3.TransactionInstruction[],
+    payer: Keypair
+  ) {
+    const sig = await processTransaction(
+      [modifyComputeUnits, ...instructions],
+      program.provider.connection,
+      payer
+    );
+    const txn = await program.provider.connection.getParsedTransaction(
+      sig.Signature,
+      {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      }
+    );
+    assert.equal(
+      sig.SignatureResult.err,
+      null,
+      `${txn?.meta?.logMessages.join("\n")}`
+    );
+  }
+
+  async function signerBalance(): Promise<bigint> {
+    const account = await getAccount(
+      program.provider.connection,
+      signerTokenAccount,
+      "confirmed"
+    );
+    return account.amount;
+  }
+
+  it("airdrop and mint", async () => {
+    for (const key of [signer, anonSigner]) {
+      await airdrop(program, key.publicKey, LAMPORTS_PER_SOL * 50_000);
+    }
+    mint = await createMint(
+      program.provider.connection,
+      signer,
+      signer.publicKey,
+      null,
+      6
+    );
+    signerTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        program.provider.connection,
+        signer,
+        mint,
+        signer.publicKey
+      )
+    ).address;
+    await mintTo(
+      program.provider.connection,
+      signer,
+      mint,
+      signerTokenAccount,
+      signer,
+      depositSize
+    );
+  });
+
+  it("create merkle", async () => {
+    const instruction = buildCreateMerkleTransactionInstruction({
+      mint,
+      signer: signer.publicKey,
+      depth,
+      depositSize,
+    });
+    await assertProcessed([instruction], signer);
+  });
+
+  it("deposit", async () => {
+    const commitmentBytes = CryptoHelper.from_children(
+      CryptoHelper.modInput(nullifier.u8Array),
+      CryptoHelper.modInput(secret.u8Array)
+    );
+    const depositInstructions = await buildDepositTransactionInstruction({
+      signer: signer.publicKey,
+      input: commitmentBytes,
+      depth,
+      connection: program.provider.connection,
+      mint,
+    });
+    await assertProcessed(depositInstructions, signer);
+    assert.equal(await signerBalance(), BigInt(0));
+    const instructions = await buildDumpProofTransactionInstructionsArray({
+      signer: signer.publicKey,
+      connection: program.provider.connection,
+      depth,
+      mint,
+    });
+    for (const instruction of instructions) {
+      await assertProcessed([instruction], signer);
+    }
+  });
+
+  it("withdraw pays out of the pool's token account", async () => {
+    const connection = program.provider.connection;
+    const zeros = await getMerkleZerosAccount(connection, depth, mint);
+    const merkle = await getMerkleAccount(connection, depth, mint);
+    const root = CryptoHelper.numberArrayToBigInt(
+      merkle.roots[merkle.currentRootIndex]
+    );
+    const proof_path: GenerateProofPath = CryptoHelper.generate_proof_path(
+      depth,
+      0
+    );
+    const pathElements: bigint[] = [];
+    const pathIndices: (0 | 1)[] = [];
+    for (const [level, p] of proof_path.entries()) {
+      if (p.length > 2) {
+        const is_left = p[2] == 0 ? 0 : 1;
+        const index = is_left ? p[0] : p[1];
+        const node = await getMerkleNodeHash(
+          connection,
+          depth,
+          index,
+          level,
+          zeros
+        );
+        pathElements.push(CryptoHelper.numberArrayToBigInt(node));
+        pathIndices.push(is_left);
+      }
+    }
+    const circuit_output = await run_circuit({
+      root,
+      nullifier: nullifier.num,
+      secret: secret.num,
+      circuit_name: CIRCUIT_NAME,
+      recipient: signer.publicKey,
+      pathElements,
+      pathIndices,
+    });
+    const proof = Array.from(
+      ZkHelper.convertProofToBytes(circuit_output.proof as any)
+    );
+    const instruction = await buildWithdrawTransactionInstruction({
+      mint,
+      connection,
+      signer: anonSigner.publicKey,
+      nullifierHash: CryptoHelper.hash(
+        CryptoHelper.numberArrayToU8IntArray(nullifier.u8Array)
+      ),
+      root: CryptoHelper.bigIntToNumberArray(root),
+      proof,
+      depth,
+      recipient: signer.publicKey,
+    });
+    await assertProcessed([instruction], anonSigner);
+    assert.equal(await signerBalance(), BigInt(depositSize));
+  });
+});
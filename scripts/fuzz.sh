@@ -0,0 +1,12 @@
+#!/bin/bash
+set -e
+export TARGET="${1}"
+export SECONDS_TO_RUN="${2:-60}"
+
+if [ -z "${TARGET}" ] ; then
+  echo "Provide fuzz target: merkle_state_roundtrip | mod_input | merkle_insert"
+  exit 1
+fi
+
+cd programs/zklsol
+cargo +nightly fuzz run "${TARGET}" -- -max_total_time="${SECONDS_TO_RUN}"